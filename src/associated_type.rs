@@ -3,7 +3,7 @@ use crate::r#type::Type;
 
 /// Defines an associated type.
 #[derive(Debug, Clone)]
-pub struct AssociatedType(pub Bound);
+pub struct AssociatedType(pub Bound, pub(crate) Vec<Bound>);
 
 impl AssociatedType {
     /// Add a bound to the associated type.
@@ -14,4 +14,17 @@ impl AssociatedType {
         self.0.bound.push(ty.into());
         self
     }
+
+    /// Add a `where` bound to the associated type, e.g., `Self: 'a` for a
+    /// generic associated type.
+    pub fn where_bound<T>(&mut self, name: impl ToString, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        self.1.push(Bound {
+            name: name.to_string(),
+            bound: vec![ty.into()],
+        });
+        self
+    }
 }
@@ -5,3 +5,19 @@ pub struct Bound {
     pub name: String,
     pub bound: Vec<Type>,
 }
+
+impl Bound {
+    /// Return a lifetime outlives bound, e.g., `'a: 'b`.
+    ///
+    /// The lifetimes may be given with or without the leading `'`.
+    pub fn lifetime(lt: impl ToString, bound_lt: impl ToString) -> Self {
+        Bound {
+            name: lifetime_name(lt),
+            bound: vec![lifetime_name(bound_lt).into()],
+        }
+    }
+}
+
+fn lifetime_name(lt: impl ToString) -> String {
+    format!("'{}", lt.to_string().trim_start_matches('\''))
+}
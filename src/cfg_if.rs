@@ -0,0 +1,72 @@
+use std::fmt::{self, Write};
+
+use crate::formatter::Formatter;
+use crate::scope::Scope;
+
+/// Defines a `cfg_if::cfg_if!` macro invocation.
+///
+/// Each arm pairs a `cfg` condition with the scope emitted when the
+/// condition holds. The generated crate must depend on the `cfg-if` crate.
+#[derive(Debug, Clone)]
+pub struct CfgIf {
+    /// Conditional arms
+    arms: Vec<(String, Scope)>,
+
+    /// Scope used when no condition holds
+    else_arm: Option<Scope>,
+}
+
+impl CfgIf {
+    /// Return a new, empty `cfg_if!` invocation.
+    pub fn new() -> Self {
+        CfgIf {
+            arms: Vec::new(),
+            else_arm: None,
+        }
+    }
+
+    /// Add an arm emitting `scope` when `condition` holds, e.g., `unix`.
+    pub fn arm(&mut self, condition: impl ToString, scope: Scope) -> &mut Self {
+        self.arms.push((condition.to_string(), scope));
+        self
+    }
+
+    /// Set the scope emitted when none of the conditions hold.
+    pub fn else_arm(&mut self, scope: Scope) -> &mut Self {
+        self.else_arm = Some(scope);
+        self
+    }
+
+    /// Formats the `cfg_if!` invocation using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        assert!(!self.arms.is_empty(), "cfg_if! requires at least one arm");
+
+        write!(fmt, "cfg_if::cfg_if!")?;
+
+        fmt.block(|fmt| {
+            for (i, (condition, scope)) in self.arms.iter().enumerate() {
+                if i != 0 {
+                    write!(fmt, " else ")?;
+                }
+
+                writeln!(fmt, "if #[cfg({})] {{", condition)?;
+                fmt.indent(|fmt| scope.fmt(fmt))?;
+                write!(fmt, "}}")?;
+            }
+
+            if let Some(ref scope) = self.else_arm {
+                writeln!(fmt, " else {{")?;
+                fmt.indent(|fmt| scope.fmt(fmt))?;
+                write!(fmt, "}}")?;
+            }
+
+            writeln!(fmt)
+        })
+    }
+}
+
+impl Default for CfgIf {
+    fn default() -> Self {
+        Self::new()
+    }
+}
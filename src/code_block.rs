@@ -0,0 +1,57 @@
+use std::fmt::{self, Write};
+
+use crate::formatter::Formatter;
+
+/// Defines a block of arbitrary code.
+///
+/// Unlike raw strings, the code is re-indented: leading and trailing blank
+/// lines are dropped and the common leading whitespace is stripped from all
+/// lines, so the code is emitted at the indentation level of the enclosing
+/// scope.
+#[derive(Debug, Clone)]
+pub struct CodeBlock {
+    code: String,
+}
+
+impl CodeBlock {
+    /// Return a new code block with the provided code.
+    pub fn new(code: impl ToString) -> Self {
+        CodeBlock {
+            code: code.to_string(),
+        }
+    }
+
+    /// Formats the code block using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        let lines: Vec<&str> = self.code.lines().collect();
+
+        let start = lines.iter().position(|l| !is_blank(l));
+        let end = lines.iter().rposition(|l| !is_blank(l));
+
+        let lines = match (start, end) {
+            (Some(start), Some(end)) => &lines[start..=end],
+            _ => &[][..],
+        };
+
+        let indent = lines
+            .iter()
+            .filter(|l| !is_blank(l))
+            .map(|l| l.len() - l.trim_start().len())
+            .min()
+            .unwrap_or(0);
+
+        for line in lines {
+            if is_blank(line) {
+                writeln!(fmt)?;
+            } else {
+                writeln!(fmt, "{}", &line[indent..])?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
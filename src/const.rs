@@ -4,6 +4,52 @@ use crate::Formatter;
 use core::fmt;
 use std::fmt::Write;
 
+/// Defines a literal value for a constant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LiteralValue {
+    /// An integer literal, e.g., `42`.
+    Int(i64),
+
+    /// A floating point literal, e.g., `1.5`.
+    Float(f64),
+
+    /// A boolean literal.
+    Bool(bool),
+
+    /// A string literal, e.g., `"foo"`.
+    Str(String),
+
+    /// A byte string literal, e.g., `b"foo"`.
+    Bytes(Vec<u8>),
+}
+
+impl LiteralValue {
+    /// Returns the literal as a Rust expression of type `ty`.
+    ///
+    /// `ty` is used to name the special float values, e.g., `f32::NAN`.
+    fn to_expr(&self, ty: &Type) -> String {
+        match *self {
+            LiteralValue::Int(v) => v.to_string(),
+            LiteralValue::Float(v) if v.is_nan() => format!("{}::NAN", ty.name()),
+            LiteralValue::Float(v) if v == f64::INFINITY => format!("{}::INFINITY", ty.name()),
+            LiteralValue::Float(v) if v == f64::NEG_INFINITY => {
+                format!("{}::NEG_INFINITY", ty.name())
+            }
+            LiteralValue::Float(v) => format!("{:?}", v),
+            LiteralValue::Bool(v) => v.to_string(),
+            LiteralValue::Str(ref v) => format!("{:?}", v),
+            LiteralValue::Bytes(ref v) => {
+                let escaped: String = v
+                    .iter()
+                    .flat_map(|b| std::ascii::escape_default(*b))
+                    .map(char::from)
+                    .collect();
+                format!("b\"{}\"", escaped)
+            }
+        }
+    }
+}
+
 /// Defines a constant.
 #[derive(Debug, Clone)]
 pub struct Const {
@@ -15,6 +61,7 @@ pub struct Const {
 }
 
 impl Const {
+    /// Return a new constant with the provided type.
     pub fn new<T>(ty: T) -> Self
     where
         T: Into<Type>,
@@ -34,26 +81,63 @@ impl Const {
         self
     }
 
+    /// Returns the constant documentation, if any.
+    pub fn documentation(&self) -> Option<&str> {
+        self.docs.as_ref().map(Docs::as_str)
+    }
+
+    /// Set the constant visibility.
     pub fn vis(&mut self, vis: impl ToString) -> &mut Self {
         self.vis = vis.to_string();
         self
     }
 
+    /// Set the constant type.
     pub fn ty(&mut self, ty: impl ToString) -> &mut Self {
         self.ty = Type::new(ty.to_string());
         self
     }
 
+    /// Set the constant name.
     pub fn name(&mut self, name: impl ToString) -> &mut Self {
         self.name = name.to_string();
         self
     }
 
+    /// Returns the name of the constant.
+    pub(crate) fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the constant value.
+    ///
+    /// The value is included verbatim in the formatted string.
     pub fn value(&mut self, value: impl ToString) -> &mut Self {
         self.value = value.to_string();
         self
     }
 
+    /// Set the constant value to a literal.
+    ///
+    /// Unlike [`value`](#method.value), the literal is always rendered as a
+    /// valid Rust expression, with strings and byte strings escaped. Special
+    /// float values are named after the constant's type, e.g., `f32::NAN`.
+    ///
+    /// ```
+    /// use codegen::{LiteralValue, Scope};
+    ///
+    /// let mut scope = Scope::new();
+    ///
+    /// scope
+    ///     .new_const("&str")
+    ///     .name("GREETING")
+    ///     .value_lit(LiteralValue::Str("hello".to_string()));
+    /// ```
+    pub fn value_lit(&mut self, lit: LiteralValue) -> &mut Self {
+        self.value = lit.to_expr(&self.ty);
+        self
+    }
+
     /// Formats the function using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         if let Some(ref docs) = self.docs {
@@ -14,6 +14,10 @@ impl Docs {
         }
     }
 
+    pub fn as_str(&self) -> &str {
+        &self.docs
+    }
+
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         for line in self.docs.lines() {
             write!(fmt, "///")?;
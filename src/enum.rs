@@ -1,6 +1,10 @@
 use std::fmt;
 
+use crate::block::Block;
+use crate::fields::Fields;
 use crate::formatter::Formatter;
+use crate::function::Function;
+use crate::r#impl::Impl;
 use crate::type_def::TypeDef;
 use crate::variant::Variant;
 
@@ -27,6 +31,22 @@ impl Enum {
         &self.type_def.ty
     }
 
+    /// Rename the enum.
+    pub fn rename(&mut self, new_name: impl ToString) -> &mut Self {
+        self.type_def.ty.set_name(new_name);
+        self
+    }
+
+    /// Returns the number of variants of the enum.
+    pub fn variant_count(&self) -> usize {
+        self.variants.len()
+    }
+
+    /// Returns `true` if no variant of the enum has any fields.
+    pub fn is_fieldless(&self) -> bool {
+        self.variants.iter().all(|v| v.is_unit())
+    }
+
     /// Set the enum visibility.
     pub fn vis(&mut self, vis: impl ToString) -> &mut Self {
         self.type_def.vis(vis);
@@ -54,6 +74,11 @@ impl Enum {
         self
     }
 
+    /// Returns the enum documentation, if any.
+    pub fn documentation(&self) -> Option<&str> {
+        self.type_def.documentation()
+    }
+
     /// Add a new type that the struct should derive.
     pub fn derive(&mut self, name: impl ToString) -> &mut Self {
         self.type_def.derive(name);
@@ -72,6 +97,17 @@ impl Enum {
         self
     }
 
+    /// Insert an attribute at the given position among the enum's
+    /// attributes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of attributes.
+    pub fn attribute_at(&mut self, index: usize, attr: impl ToString) -> &mut Self {
+        self.type_def.attribute_at(index, attr);
+        self
+    }
+
     /// Add an arbitrary macro.
     pub fn r#macro(&mut self, r#macro: impl ToString) -> &mut Self {
         self.type_def.r#macro(r#macro);
@@ -90,6 +126,189 @@ impl Enum {
         self
     }
 
+    /// Treat the enum as a C-like enum, returning its `u8` conversion impls.
+    ///
+    /// This sets `#[repr(u8)]` on the enum and returns an
+    /// `impl TryFrom<u8> for Enum`, which matches each discriminant, and an
+    /// `impl From<Enum> for u8`, which provides `Into<u8>` via `self as u8`.
+    /// An infallible `From<u8>` is not generated as it would conflict with
+    /// the `TryFrom<u8>` impl.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any variant of the enum has fields.
+    pub fn c_like(&mut self) -> Vec<Impl> {
+        assert!(
+            self.is_fieldless(),
+            "c-like enums must only have unit variants"
+        );
+
+        self.repr("u8");
+
+        let name = self.ty().name().to_string();
+
+        let mut try_from = Impl::new(self.ty());
+        try_from
+            .impl_trait("TryFrom<u8>")
+            .associate_type("Error", "u8");
+
+        let mut body = Block::new("match value");
+        for variant in &self.variants {
+            body.line(format!(
+                "v if v == {name}::{variant} as u8 => Ok({name}::{variant}),",
+                name = name,
+                variant = variant.name()
+            ));
+        }
+        body.line("_ => Err(value),");
+
+        try_from
+            .new_fn("try_from")
+            .arg("value", "u8")
+            .ret("Result<Self, Self::Error>")
+            .push_block(body);
+
+        let mut into = Impl::new("u8");
+        let mut from_trait = Type::new("From");
+        from_trait.generic(self.ty());
+        into.impl_trait(from_trait)
+            .new_fn("from")
+            .arg("value", self.ty())
+            .ret("u8")
+            .line("value as u8");
+
+        vec![try_from, into]
+    }
+
+    /// Return an `impl std::str::FromStr` block parsing the enum from its
+    /// variant names.
+    ///
+    /// When `case_sensitive` is `false`, the input is lowercased before being
+    /// matched, so `"foo"`, `"Foo"` and `"FOO"` all parse as `Foo`. Unknown
+    /// input returns an `Err` with a `String` message.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any variant of the enum has fields, or if `case_sensitive` is
+    /// `false` and two variants differ only in case, e.g., `Foo` and `FOO`.
+    pub fn generate_from_str_impl(&self, case_sensitive: bool) -> Impl {
+        assert!(
+            self.is_fieldless(),
+            "FromStr can only be generated for enums with unit variants"
+        );
+
+        let mut body = if case_sensitive {
+            Block::new("match s")
+        } else {
+            Block::new("match s.to_lowercase().as_str()")
+        };
+
+        let mut patterns: Vec<(String, &str)> = vec![];
+
+        for variant in &self.variants {
+            let name = variant.name();
+            let pattern = if case_sensitive {
+                name.to_string()
+            } else {
+                name.to_lowercase()
+            };
+
+            if let Some((_, other)) = patterns.iter().find(|(p, _)| *p == pattern) {
+                panic!(
+                    "variants `{}` and `{}` both parse from `{}`",
+                    other, name, pattern
+                );
+            }
+            patterns.push((pattern.clone(), name));
+
+            body.line(format!("\"{}\" => Ok(Self::{}),", pattern, name));
+        }
+        body.line(format!(
+            "_ => Err(format!(\"unknown {} variant `{{}}`\", s)),",
+            self.ty().name()
+        ));
+
+        let mut ret = self.type_def.new_impl();
+        ret.impl_trait("std::str::FromStr")
+            .associate_type("Err", "String")
+            .new_fn("from_str")
+            .arg("s", "&str")
+            .ret("Result<Self, Self::Err>")
+            .push_block(body);
+
+        ret
+    }
+
+    /// Return a constructor function for each variant of the enum.
+    ///
+    /// Each function is named after its variant in snake_case, takes the
+    /// variant's fields as arguments and returns the variant. Tuple fields
+    /// are named `val`, or `val0`, `val1`, etc. when there are several. The
+    /// functions are meant to be pushed into an `impl` block for the enum.
+    ///
+    /// Function names that are Rust keywords are emitted as raw identifiers,
+    /// e.g., `r#type` for a `Type` variant.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variant's function name would be `self`, `super` or
+    /// `crate`, which cannot be used as raw identifiers.
+    ///
+    /// ```
+    /// use codegen::{Enum, Impl};
+    ///
+    /// let mut foo = Enum::new("Foo");
+    /// foo.new_variant("Bar").tuple("u32");
+    /// foo.new_variant("Baz");
+    ///
+    /// let mut imp = Impl::new(foo.ty());
+    /// for func in foo.generate_constructors() {
+    ///     imp.push_fn(func);
+    /// }
+    /// ```
+    pub fn generate_constructors(&self) -> Vec<Function> {
+        let name = self.ty().name();
+
+        self.variants
+            .iter()
+            .map(|variant| {
+                let mut func = Function::new(to_fn_name(variant.name()));
+                func.ret(self.ty());
+
+                let path = format!("{}::{}", name, variant.name());
+
+                match *variant.fields() {
+                    Fields::Empty => {
+                        func.line(path);
+                    }
+                    Fields::Tuple(ref tys) => {
+                        let args: Vec<String> = if tys.len() == 1 {
+                            vec!["val".to_string()]
+                        } else {
+                            (0..tys.len()).map(|i| format!("val{}", i)).collect()
+                        };
+
+                        for (arg, (_, ty)) in args.iter().zip(tys) {
+                            func.arg(arg, ty);
+                        }
+
+                        func.line(format!("{}({})", path, args.join(", ")));
+                    }
+                    Fields::Named(ref fields) => {
+                        for field in fields {
+                            func.arg(&field.name, &field.ty);
+                        }
+
+                        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+                        func.line(format!("{} {{ {} }}", path, names.join(", ")));
+                    }
+                }
+
+                func
+            })
+            .collect()
+    }
+
     /// Formats the enum using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         self.type_def.fmt_head("enum", &[], fmt)?;
@@ -103,3 +322,52 @@ impl Enum {
         })
     }
 }
+
+/// Keywords that can only be used as identifiers in their raw form.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Convert a variant name to a snake_case function name, using a raw
+/// identifier if the name is a keyword, e.g., `Type` to `r#type`.
+fn to_fn_name(variant: &str) -> String {
+    let name = to_snake_case(variant);
+
+    assert!(
+        !matches!(name.as_str(), "self" | "super" | "crate" | "Self"),
+        "variant `{}` cannot be converted to a fn name: `{}` cannot be a raw identifier",
+        variant,
+        name
+    );
+
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+/// Convert a CamelCase name to snake_case, e.g., `HttpError` to `http_error`.
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut ret = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                ret.push('_');
+            }
+        }
+
+        ret.extend(c.to_lowercase());
+    }
+
+    ret
+}
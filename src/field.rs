@@ -20,6 +20,12 @@ pub struct Field {
 
     /// The visibility of the field
     pub visibility: Option<String>,
+
+    /// Whether the field is skipped by generated `Hash` impls
+    pub skip_hash: bool,
+
+    /// Comment emitted above the field to introduce a group of fields
+    pub group_comment: Option<String>,
 }
 
 impl Field {
@@ -35,6 +41,8 @@ impl Field {
             annotation: Vec::new(),
             value: String::new(),
             visibility: None,
+            skip_hash: false,
+            group_comment: None,
         }
     }
 
@@ -45,14 +53,38 @@ impl Field {
     }
 
     /// Set field's annotation.
+    ///
+    /// The annotation is emitted verbatim above the field, e.g.,
+    /// `"#[serde(skip)]"`.
     pub fn annotation(&mut self, annotation: impl ToString) -> &mut Self {
         self.annotation.push(annotation.to_string());
         self
     }
 
+    /// Add an attribute to the field, emitted as `#[attr]` above the field.
+    pub fn attr(&mut self, attr: impl ToString) -> &mut Self {
+        self.annotation(format!("#[{}]", attr.to_string()))
+    }
+
+    /// Set whether the field is skipped by generated `Hash` impls.
+    ///
+    /// See [`Struct::generate_hash_impl`](crate::Struct::generate_hash_impl).
+    pub fn skip_hash(&mut self, skip: bool) -> &mut Self {
+        self.skip_hash = skip;
+        self
+    }
+
     /// Set the visibility of the field
     pub fn vis(&mut self, visibility: impl ToString) -> &mut Self {
         self.visibility = Some(visibility.to_string());
         self
     }
+
+    /// Wrap the field type in an `Option`, e.g., `T` becomes `Option<T>`.
+    pub fn optional(&mut self) -> &mut Self {
+        let mut ty = Type::new("Option");
+        ty.generic(self.ty.clone());
+        self.ty = ty;
+        self
+    }
 }
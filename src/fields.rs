@@ -9,7 +9,7 @@ use crate::r#type::Type;
 #[derive(Debug, Clone)]
 pub enum Fields {
     Empty,
-    Tuple(Vec<Type>),
+    Tuple(Vec<(Option<String>, Type)>),
     Named(Vec<Field>),
 }
 
@@ -39,6 +39,8 @@ impl Fields {
             annotation: Vec::new(),
             value: String::new(),
             visibility: None,
+            skip_hash: false,
+            group_comment: None,
         })
     }
 
@@ -58,12 +60,23 @@ impl Fields {
     where
         T: Into<Type>,
     {
+        self.push_tuple(None, ty.into())
+    }
+
+    pub fn tuple_with_vis<T>(&mut self, vis: impl ToString, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        self.push_tuple(Some(vis.to_string()), ty.into())
+    }
+
+    fn push_tuple(&mut self, vis: Option<String>, ty: Type) -> &mut Self {
         match *self {
             Fields::Empty => {
-                *self = Fields::Tuple(vec![ty.into()]);
+                *self = Fields::Tuple(vec![(vis, ty)]);
             }
             Fields::Tuple(ref mut fields) => {
-                fields.push(ty.into());
+                fields.push((vis, ty));
             }
             _ => panic!("field list is tuple"),
         }
@@ -78,6 +91,9 @@ impl Fields {
 
                 fmt.block(|fmt| {
                     for f in fields {
+                        if let Some(ref comment) = f.group_comment {
+                            writeln!(fmt, "// {}", comment)?;
+                        }
                         if !f.documentation.is_empty() {
                             for doc in f.documentation.lines() {
                                 writeln!(fmt, "/// {}", doc)?;
@@ -104,10 +120,13 @@ impl Fields {
 
                 write!(fmt, "(")?;
 
-                for (i, ty) in tys.iter().enumerate() {
+                for (i, (vis, ty)) in tys.iter().enumerate() {
                     if i != 0 {
                         write!(fmt, ", ")?;
                     }
+                    if let Some(ref vis) = *vis {
+                        write!(fmt, "{} ", vis)?;
+                    }
                     ty.fmt(fmt)?;
                 }
 
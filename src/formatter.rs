@@ -6,6 +6,53 @@ use crate::r#type::Type;
 
 const DEFAULT_INDENT: usize = 4;
 
+/// The Rust edition targeted by the generated code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RustEdition {
+    /// Rust 2015
+    Edition2015,
+
+    /// Rust 2018
+    Edition2018,
+
+    /// Rust 2021
+    #[default]
+    Edition2021,
+
+    /// Rust 2024
+    Edition2024,
+}
+
+impl RustEdition {
+    /// Returns the edition as passed to `rustc --edition`, e.g., `"2021"`.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            RustEdition::Edition2015 => "2015",
+            RustEdition::Edition2018 => "2018",
+            RustEdition::Edition2021 => "2021",
+            RustEdition::Edition2024 => "2024",
+        }
+    }
+}
+
+/// Options used by a [`Formatter`].
+#[derive(Debug, Clone, Default)]
+pub struct FormatterConfig {
+    /// The Rust edition targeted by the generated code.
+    ///
+    /// The generated code is currently the same for every edition.
+    pub edition: RustEdition,
+
+    /// Whether to omit `extern "Rust"` from functions, as it is the default
+    /// ABI. By default it is emitted as specified.
+    pub omit_rust_abi: bool,
+
+    /// Whether to move bounds declared inline with generics, e.g.,
+    /// `<T: Clone>`, to the `where` clause. By default generics are emitted
+    /// as specified.
+    pub always_use_where_clause: bool,
+}
+
 /// Configures how a scope is formatted.
 #[derive(Debug)]
 pub struct Formatter<'a> {
@@ -17,18 +64,33 @@ pub struct Formatter<'a> {
 
     /// Number of spaces per indentiation
     indent: usize,
+
+    /// Formatting options
+    config: FormatterConfig,
 }
 
 impl<'a> Formatter<'a> {
     /// Return a new formatter that writes to the given string.
     pub fn new(dst: &'a mut String) -> Self {
+        Formatter::with_config(dst, &FormatterConfig::default())
+    }
+
+    /// Return a new formatter that writes to the given string using the
+    /// given options.
+    pub fn with_config(dst: &'a mut String, config: &FormatterConfig) -> Self {
         Formatter {
             dst,
             spaces: 0,
             indent: DEFAULT_INDENT,
+            config: config.clone(),
         }
     }
 
+    /// Returns the options used by the formatter.
+    pub fn config(&self) -> &FormatterConfig {
+        &self.config
+    }
+
     /// Wrap the given function inside a block.
     pub fn block<F>(&mut self, f: F) -> fmt::Result
     where
@@ -44,6 +106,15 @@ impl<'a> Formatter<'a> {
         Ok(())
     }
 
+    /// Write each attribute on its own line as `#[attr]`.
+    pub fn emit_attributes(&mut self, attrs: &[String]) -> fmt::Result {
+        for attr in attrs {
+            writeln!(self, "#[{}]", attr)?;
+        }
+
+        Ok(())
+    }
+
     /// Call the given function with the indentation level incremented by one.
     pub fn indent<F, R>(&mut self, f: F) -> R
     where
@@ -60,6 +131,20 @@ impl<'a> Formatter<'a> {
         self.dst.is_empty() || self.dst.as_bytes().last() == Some(&b'\n')
     }
 
+    /// Start a new line by writing the current indentation.
+    ///
+    /// Does nothing if the destination is not at the start of a line.
+    pub fn begin_line(&mut self) {
+        if self.is_start_of_line() {
+            self.push_spaces();
+        }
+    }
+
+    /// End the current line.
+    pub fn end_line(&mut self) {
+        self.dst.push('\n');
+    }
+
     fn push_spaces(&mut self) {
         for _ in 0..self.spaces {
             self.dst.push(' ');
@@ -99,6 +184,57 @@ impl<'a> fmt::Write for Formatter<'a> {
     }
 }
 
+/// Returns the generics and `where` bounds to emit for an item.
+///
+/// With [`FormatterConfig::always_use_where_clause`], inline bounds are
+/// stripped from the generics and prepended to the `where` bounds.
+pub(crate) fn hoist_bounds(
+    generics: &[String],
+    bounds: &[Bound],
+    fmt: &Formatter<'_>,
+) -> (Vec<String>, Vec<Bound>) {
+    if !fmt.config().always_use_where_clause {
+        return (generics.to_vec(), bounds.to_vec());
+    }
+
+    let mut hoisted = vec![];
+
+    let generics = generics
+        .iter()
+        .map(|generic| match split_inline_bound(generic) {
+            Some((name, bound)) => {
+                hoisted.push(Bound {
+                    name: name.to_string(),
+                    bound: vec![bound.into()],
+                });
+                name.to_string()
+            }
+            None => generic.clone(),
+        })
+        .collect();
+
+    hoisted.extend(bounds.iter().cloned());
+
+    (generics, hoisted)
+}
+
+/// Split a generic with an inline bound, e.g., `T: Clone`, into the generic
+/// name and the bound.
+///
+/// Const generics and path separators (`::`) are not considered bounds.
+pub(crate) fn split_inline_bound(generic: &str) -> Option<(&str, &str)> {
+    if generic.starts_with("const ") {
+        return None;
+    }
+
+    let bytes = generic.as_bytes();
+    let pos = (0..bytes.len()).find(|&i| {
+        bytes[i] == b':' && bytes.get(i + 1) != Some(&b':') && (i == 0 || bytes[i - 1] != b':')
+    })?;
+
+    Some((generic[..pos].trim(), generic[pos + 1..].trim()))
+}
+
 /// Format generics.
 pub fn fmt_generics(generics: &[String], fmt: &mut Formatter<'_>) -> fmt::Result {
     if !generics.is_empty() {
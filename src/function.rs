@@ -6,7 +6,7 @@ use crate::bound::Bound;
 use crate::docs::Docs;
 use crate::field::Field;
 use crate::formatter::Formatter;
-use crate::formatter::{fmt_bounds, fmt_generics};
+use crate::formatter::{fmt_bounds, fmt_generics, hoist_bounds};
 
 use crate::r#type::Type;
 
@@ -25,6 +25,9 @@ pub struct Function {
     /// Function visibility
     vis: Option<String>,
 
+    /// Function lifetimes
+    lifetimes: Vec<String>,
+
     /// Function generics
     generics: Vec<String>,
 
@@ -49,8 +52,20 @@ pub struct Function {
     /// Function `extern` ABI
     extern_abi: Option<String>,
 
+    /// Whether or not this function is `const`
+    r#const: bool,
+
     /// Whether or not this function is `async` or not
     r#async: bool,
+
+    /// Whether or not this function is a `default` specializable fn
+    default: bool,
+
+    /// Whether or not this function is `unsafe`
+    r#unsafe: bool,
+
+    /// Documented default values of `Option` arguments
+    arg_defaults: Vec<String>,
 }
 
 impl Function {
@@ -61,6 +76,7 @@ impl Function {
             docs: None,
             allow: None,
             vis: None,
+            lifetimes: vec![],
             generics: vec![],
             arg_self: None,
             args: vec![],
@@ -69,16 +85,36 @@ impl Function {
             body: Some(vec![]),
             attributes: vec![],
             extern_abi: None,
+            r#const: false,
             r#async: false,
+            default: false,
+            r#unsafe: false,
+            arg_defaults: vec![],
         }
     }
 
+    /// Returns the name of the function.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Rename the function.
+    pub fn rename(&mut self, new_name: impl ToString) -> &mut Self {
+        self.name = new_name.to_string();
+        self
+    }
+
     /// Set the function documentation.
     pub fn doc(&mut self, docs: impl ToString) -> &mut Self {
         self.docs = Some(Docs::new(docs));
         self
     }
 
+    /// Returns the function documentation, if any.
+    pub fn documentation(&self) -> Option<&str> {
+        self.docs.as_ref().map(Docs::as_str)
+    }
+
     /// Specify lint attribute to supress a warning or error.
     pub fn allow(&mut self, allow: impl ToString) -> &mut Self {
         self.allow = Some(allow.to_string());
@@ -91,9 +127,51 @@ impl Function {
         self
     }
 
+    /// Set whether this function is const or not
+    pub fn set_const(&mut self, r#const: bool) -> &mut Self {
+        self.r#const = r#const;
+        self
+    }
+
+    /// Returns `true` if the function is `const`.
+    pub fn is_const(&self) -> bool {
+        self.r#const
+    }
+
     /// Set whether this function is async or not
+    ///
+    /// # Panics
+    ///
+    /// Panics if `r#async` is `true` and the function returns `!`.
     pub fn set_async(&mut self, r#async: bool) -> &mut Self {
         self.r#async = r#async;
+        self.assert_async_ret();
+        self
+    }
+
+    /// Returns `true` if the function is `async`.
+    pub fn is_async(&self) -> bool {
+        self.r#async
+    }
+
+    /// Set whether this function is unsafe or not
+    pub fn set_unsafe(&mut self, r#unsafe: bool) -> &mut Self {
+        self.r#unsafe = r#unsafe;
+        self
+    }
+
+    /// Returns `true` if the function is `unsafe`.
+    pub fn is_unsafe(&self) -> bool {
+        self.r#unsafe
+    }
+
+    /// Set whether this function is a `default fn` or not.
+    ///
+    /// `default fn` is used by specialization, which is unstable and requires
+    /// the generated crate to enable `#![feature(specialization)]`.
+    #[cfg(feature = "specialization")]
+    pub fn default_fn(&mut self, default: bool) -> &mut Self {
+        self.default = default;
         self
     }
 
@@ -103,6 +181,15 @@ impl Function {
         self
     }
 
+    /// Add a lifetime to the function.
+    ///
+    /// Lifetimes are emitted before any generics in the function's angle
+    /// brackets (`fn foo<'a, T>`).
+    pub fn lifetime(&mut self, name: impl ToString) -> &mut Self {
+        self.lifetimes.push(name.to_string());
+        self
+    }
+
     /// Add `self` as a function argument.
     pub fn arg_self(&mut self) -> &mut Self {
         self.arg_self = Some("self".to_string());
@@ -121,6 +208,19 @@ impl Function {
         self
     }
 
+    /// Add `self` with an explicit receiver type as a function argument,
+    /// e.g., `self: Box<Self>`.
+    pub fn self_type<T>(&mut self, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        let mut receiver = "self: ".to_string();
+        ty.into().fmt(&mut Formatter::new(&mut receiver)).unwrap();
+
+        self.arg_self = Some(receiver);
+        self
+    }
+
     /// Add a function argument.
     pub fn arg<T>(&mut self, name: impl ToString, ty: T) -> &mut Self
     where
@@ -136,20 +236,118 @@ impl Function {
             annotation: Vec::new(),
             value: String::new(),
             visibility: None,
+            skip_hash: false,
+            group_comment: None,
         });
 
         self
     }
 
+    /// Add a function argument bound to a pattern, e.g., `(x, y): (u32, u32)`.
+    ///
+    /// The pattern is included verbatim in the formatted string.
+    pub fn arg_pattern<T>(&mut self, pat: impl ToString, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        self.arg(pat, ty)
+    }
+
+    /// Add an optional function argument with a documented default value.
+    ///
+    /// The argument is emitted as `Option<Type>` and a
+    /// ``Default for `name`: `expr` `` line is appended to the function
+    /// documentation. The function body is responsible for applying the
+    /// default.
+    pub fn arg_with_default<T>(
+        &mut self,
+        name: impl ToString,
+        ty: T,
+        default_expr: impl ToString,
+    ) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        let name = name.to_string();
+
+        let mut option = Type::new("Option");
+        option.generic(ty);
+
+        self.arg_defaults.push(format!(
+            "Default for `{}`: `{}`",
+            name,
+            default_expr.to_string()
+        ));
+        self.arg(name, option)
+    }
+
     /// Set the function return type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function is `async` and `ty` is `!`.
     pub fn ret<T>(&mut self, ty: T) -> &mut Self
     where
         T: Into<Type>,
     {
         self.ret = Some(ty.into());
+        self.assert_async_ret();
         self
     }
 
+    /// Set the function return type to `Self`.
+    pub fn ret_self(&mut self) -> &mut Self {
+        self.ret("Self")
+    }
+
+    /// Set the function return type to `&Self`.
+    pub fn ret_ref_self(&mut self) -> &mut Self {
+        self.ret("&Self")
+    }
+
+    /// Set the function return type to `&mut Self`.
+    pub fn ret_mut_ref_self(&mut self) -> &mut Self {
+        self.ret("&mut Self")
+    }
+
+    /// Set the function return type to a reference with a named lifetime,
+    /// e.g., `&'a str`, or `&'a mut str` if `mutable` is set.
+    ///
+    /// The lifetime may be given with or without the leading `'`.
+    ///
+    /// ```
+    /// use codegen::Function;
+    ///
+    /// let mut func = Function::new("name");
+    ///
+    /// // returns `&'a str`
+    /// func.generic("'a").ret_lifetime("a", "str", false);
+    /// ```
+    pub fn ret_lifetime<T>(&mut self, lt: &str, inner: T, mutable: bool) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        let inner = inner.into();
+        let lt = lt.trim_start_matches('\'');
+        let mutability = if mutable { "mut " } else { "" };
+
+        let mut ty = Type::new(format!("&'{} {}{}", lt, mutability, inner.name()));
+        for generic in inner.generics() {
+            ty.generic(generic);
+        }
+
+        self.ret(ty)
+    }
+
+    /// Set the function return type to `!` for functions that never return.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the function is `async`.
+    pub fn ret_never(&mut self) -> &mut Self {
+        self.ret(Type::never())
+    }
+
     /// Add a `where` bound to the function.
     pub fn bound<T>(&mut self, name: impl ToString, ty: T) -> &mut Self
     where
@@ -162,6 +360,31 @@ impl Function {
         self
     }
 
+    /// Add a `where` bound to the function, combining it with any existing
+    /// bound on the same name, e.g., `T: Clone + Send`.
+    pub fn where_bound<T>(&mut self, name: impl ToString, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        let name = name.to_string();
+
+        match self.bounds.iter_mut().find(|b| b.name == name) {
+            Some(existing) => existing.bound.push(ty.into()),
+            None => self.bounds.push(Bound {
+                name,
+                bound: vec![ty.into()],
+            }),
+        }
+
+        self
+    }
+
+    /// Add a lifetime `where` bound to the function, e.g., `'a: 'b`.
+    pub fn lifetime_bound(&mut self, lt: impl ToString, bound_lt: impl ToString) -> &mut Self {
+        self.bounds.push(Bound::lifetime(lt, bound_lt));
+        self
+    }
+
     /// Push a line to the function implementation.
     pub fn line<T>(&mut self, line: T) -> &mut Self
     where
@@ -189,7 +412,85 @@ impl Function {
         self
     }
 
+    /// Add an attribute to the function.
+    ///
+    /// This is the same as [`attr`](#method.attr), accepting any
+    /// `impl ToString`.
+    pub fn push_attr(&mut self, attr: impl ToString) -> &mut Self {
+        self.attributes.push(attr.to_string());
+        self
+    }
+
+    /// Returns the attributes of the function, without the `#[...]`.
+    pub fn attrs(&self) -> &[String] {
+        &self.attributes
+    }
+
+    /// Insert an attribute at the given position among the function's
+    /// attributes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of attributes.
+    pub fn attribute_at(&mut self, index: usize, attr: impl ToString) -> &mut Self {
+        self.attributes.insert(index, attr.to_string());
+        self
+    }
+
+    /// Only compile the function when the given condition holds, i.e.,
+    /// `#[cfg(condition)]`.
+    ///
+    /// A condition that is already set is not added again.
+    pub fn cfg(&mut self, condition: impl ToString) -> &mut Self {
+        let attr = format!("cfg({})", condition.to_string());
+
+        if !self.attributes.contains(&attr) {
+            self.attributes.push(attr);
+        }
+
+        self
+    }
+
+    /// Add a `#[test_case(...)]` attribute for the `test-case` crate.
+    ///
+    /// Each call adds a new test case. `test-case` generates the test
+    /// functions itself, so no `#[test]` attribute should be added.
+    ///
+    /// ```
+    /// use codegen::Function;
+    ///
+    /// let mut func = Function::new("adds");
+    ///
+    /// // add a `#[test_case(1, 2, 3)]` attribute
+    /// func.test_case(&[1, 2, 3]);
+    /// ```
+    pub fn test_case(&mut self, args: &[impl ToString]) -> &mut Self {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        self.attributes
+            .push(format!("test_case({})", args.join(", ")));
+        self
+    }
+
+    /// Add a `#[link_name = "..."]` attribute, mapping the function to a
+    /// foreign symbol with a different name.
+    ///
+    /// This is only meaningful for functions declared in `extern` blocks.
+    pub fn link_name(&mut self, name: impl ToString) -> &mut Self {
+        self.attributes
+            .push(format!("link_name = \"{}\"", name.to_string()));
+        self
+    }
+
     /// Specify an `extern` ABI for the function.
+    ///
+    /// A function with an `extern` ABI and no body lines is emitted as a
+    /// declaration ending in `;`, as in `extern` blocks.
+    ///
+    /// `extern "Rust"` is emitted explicitly unless
+    /// [`FormatterConfig::omit_rust_abi`](crate::FormatterConfig::omit_rust_abi)
+    /// is set, in which case the function is emitted as a plain `fn`,
+    /// with an empty body rather than as a declaration.
+    ///
     /// ```
     /// use codegen::Function;
     ///
@@ -203,6 +504,13 @@ impl Function {
         self
     }
 
+    /// Specify an `extern` ABI for the function, e.g., `"C"` or `"system"`.
+    ///
+    /// This is the same as [`extern_abi`](#method.extern_abi).
+    pub fn set_extern(&mut self, abi: impl ToString) -> &mut Self {
+        self.extern_abi(abi)
+    }
+
     /// Push a block to the function implementation
     pub fn push_block(&mut self, block: Block) -> &mut Self {
         self.body.get_or_insert(vec![]).push(Body::Block(block));
@@ -210,19 +518,113 @@ impl Function {
         self
     }
 
+    /// Returns an iterator over the lines of the function body.
+    ///
+    /// Only lines pushed with [`line`](#method.line) are returned, blocks
+    /// pushed with [`push_block`](#method.push_block) are skipped, including
+    /// the lines nested inside them. Since the body interleaves lines and
+    /// blocks, it cannot be borrowed as a `&[String]`.
+    pub fn body_lines(&self) -> impl Iterator<Item = &String> {
+        self.body.iter().flatten().filter_map(|b| match b {
+            Body::String(s) => Some(s),
+            Body::Block(_) => None,
+        })
+    }
+
+    /// Returns a mutable iterator over the lines of the function body.
+    ///
+    /// Only lines pushed with [`line`](#method.line) are returned, blocks
+    /// pushed with [`push_block`](#method.push_block) are skipped, including
+    /// the lines nested inside them. To add or remove lines, use
+    /// [`clear_body`](#method.clear_body) followed by
+    /// [`line`](#method.line).
+    pub fn body_lines_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        self.body.iter_mut().flatten().filter_map(|b| match b {
+            Body::String(s) => Some(s),
+            Body::Block(_) => None,
+        })
+    }
+
+    /// Remove all lines and blocks from the function body.
+    pub fn clear_body(&mut self) -> &mut Self {
+        if let Some(ref mut body) = self.body {
+            body.clear();
+        }
+
+        self
+    }
+
+    /// Assert that the function formats to `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics with a line diff of the expected and actual output if they
+    /// differ.
+    #[cfg(feature = "testing")]
+    pub fn assert_output(&self, expected: &str) {
+        let mut actual = String::new();
+        self.fmt(false, &mut Formatter::new(&mut actual)).unwrap();
+
+        let actual = actual.trim_end();
+        let expected = expected.trim_end();
+
+        if actual == expected {
+            return;
+        }
+
+        let mut diff = String::new();
+        let mut expected_lines = expected.lines();
+        let mut actual_lines = actual.lines();
+
+        loop {
+            match (expected_lines.next(), actual_lines.next()) {
+                (None, None) => break,
+                (Some(e), Some(a)) if e == a => diff.push_str(&format!("  {}\n", e)),
+                (e, a) => {
+                    if let Some(e) = e {
+                        diff.push_str(&format!("- {}\n", e));
+                    }
+                    if let Some(a) = a {
+                        diff.push_str(&format!("+ {}\n", a));
+                    }
+                }
+            }
+        }
+
+        panic!(
+            "function `{}` output does not match (- expected, + actual):\n{}",
+            self.name, diff
+        );
+    }
+
+    fn assert_async_ret(&self) {
+        if self.r#async {
+            assert!(
+                !matches!(self.ret, Some(ref ret) if ret.name() == "!"),
+                "async fns cannot return `!`"
+            );
+        }
+    }
+
     /// Formats the function using the given formatter.
     pub fn fmt(&self, is_trait: bool, fmt: &mut Formatter<'_>) -> fmt::Result {
         if let Some(ref docs) = self.docs {
             docs.fmt(fmt)?;
         }
 
+        if !self.arg_defaults.is_empty() {
+            if self.docs.is_some() {
+                writeln!(fmt, "///")?;
+            }
+
+            Docs::new(self.arg_defaults.join("\n")).fmt(fmt)?;
+        }
+
         if let Some(ref allow) = self.allow {
             writeln!(fmt, "#[allow({})]", allow)?;
         }
 
-        for attr in self.attributes.iter() {
-            writeln!(fmt, "#[{}]", attr)?;
-        }
+        fmt.emit_attributes(&self.attributes)?;
 
         if is_trait {
             assert!(
@@ -235,16 +637,42 @@ impl Function {
             write!(fmt, "{} ", vis)?;
         }
 
-        if let Some(ref extern_abi) = self.extern_abi {
-            write!(fmt, "extern \"{extern_abi}\" ", extern_abi = extern_abi)?;
+        if self.default {
+            write!(fmt, "default ")?;
+        }
+
+        // Qualifiers follow the order `const async unsafe extern`
+        if self.r#const {
+            write!(fmt, "const ")?;
         }
 
         if self.r#async {
             write!(fmt, "async ")?;
         }
 
+        if self.r#unsafe {
+            write!(fmt, "unsafe ")?;
+        }
+
+        let extern_abi = self
+            .extern_abi
+            .as_ref()
+            .filter(|abi| *abi != "Rust" || !fmt.config().omit_rust_abi);
+
+        if let Some(extern_abi) = extern_abi {
+            write!(fmt, "extern \"{extern_abi}\" ", extern_abi = extern_abi)?;
+        }
+
+        let generics: Vec<String> = self
+            .lifetimes
+            .iter()
+            .chain(self.generics.iter())
+            .cloned()
+            .collect();
+        let (generics, bounds) = hoist_bounds(&generics, &self.bounds, fmt);
+
         write!(fmt, "fn {}", self.name)?;
-        fmt_generics(&self.generics, fmt)?;
+        fmt_generics(&generics, fmt)?;
 
         write!(fmt, "(")?;
 
@@ -268,9 +696,10 @@ impl Function {
             ret.fmt(fmt)?;
         }
 
-        fmt_bounds(&self.bounds, fmt)?;
+        fmt_bounds(&bounds, fmt)?;
 
         match self.body {
+            Some(ref body) if body.is_empty() && extern_abi.is_some() => writeln!(fmt, ";"),
             Some(ref body) => fmt.block(|fmt| {
                 for b in body {
                     b.fmt(fmt)?;
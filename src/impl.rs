@@ -2,7 +2,7 @@ use std::fmt::{self, Write};
 
 use crate::bound::Bound;
 use crate::field::Field;
-use crate::formatter::{fmt_bounds, fmt_generics, Formatter};
+use crate::formatter::{fmt_bound_rhs, fmt_bounds, fmt_generics, hoist_bounds, Formatter};
 use crate::function::Function;
 
 use crate::r#type::Type;
@@ -13,6 +13,9 @@ pub struct Impl {
     /// The struct being implemented
     target: Type,
 
+    /// Impl level lifetimes
+    lifetimes: Vec<String>,
+
     /// Impl level generics
     generics: Vec<String>,
 
@@ -25,6 +28,9 @@ pub struct Impl {
     /// Associated types
     assoc_tys: Vec<Field>,
 
+    /// Bounds on associated types, keyed by the associated type name
+    assoc_ty_bounds: Vec<Bound>,
+
     /// Bounds
     bounds: Vec<Bound>,
 
@@ -41,16 +47,60 @@ impl Impl {
     {
         Impl {
             target: target.into(),
+            lifetimes: Vec::new(),
             generics: Vec::new(),
             impl_trait: None,
             assoc_csts: Vec::new(),
             assoc_tys: Vec::new(),
+            assoc_ty_bounds: Vec::new(),
             bounds: Vec::new(),
             fns: Vec::new(),
             macros: Vec::new(),
         }
     }
 
+    /// Return a complete `impl Iterator for Target` definition.
+    ///
+    /// The impl block sets `type Item` and defines `fn next(&mut self)` with
+    /// the provided body.
+    ///
+    /// ```
+    /// use codegen::Impl;
+    ///
+    /// let imp = Impl::iterator_impl("Counter", "u32", "self.count.checked_sub(1)");
+    /// ```
+    pub fn iterator_impl<T, U>(target: T, item_type: U, next_body: impl ToString) -> Self
+    where
+        T: Into<Type>,
+        U: Into<Type>,
+    {
+        let item_type = item_type.into();
+
+        let mut ret = Impl::new(target);
+        ret.impl_trait("Iterator")
+            .associate_type("Item", &item_type);
+
+        let mut ret_type = Type::new("Option");
+        ret_type.generic(item_type);
+
+        ret.new_fn("next")
+            .arg_mut_self()
+            .ret(ret_type)
+            .line(next_body);
+
+        ret
+    }
+
+    /// Returns the name of the target type, without any generics.
+    pub fn target_name(&self) -> &str {
+        let name = self.target.name();
+
+        match name.find('<') {
+            Some(i) => &name[..i],
+            None => name,
+        }
+    }
+
     /// Add a generic to the impl block.
     ///
     /// This adds the generic for the block (`impl<T>`) and not the target type.
@@ -59,6 +109,16 @@ impl Impl {
         self
     }
 
+    /// Add a lifetime to the impl block.
+    ///
+    /// Lifetimes are emitted before any generics in the impl block's angle
+    /// brackets (`impl<'a, T>`). The lifetime may include bounds, e.g.
+    /// `"'b: 'a"`.
+    pub fn generic_lifetime(&mut self, lt: impl ToString) -> &mut Self {
+        self.lifetimes.push(lt.to_string());
+        self
+    }
+
     /// Add a generic to the target type.
     pub fn target_generic<T>(&mut self, ty: T) -> &mut Self
     where
@@ -83,6 +143,18 @@ impl Impl {
         self
     }
 
+    /// Insert a `#[attr]` attribute at the given position among the impl
+    /// block's macros.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of macros.
+    pub fn attribute_at(&mut self, index: usize, attr: impl ToString) -> &mut Self {
+        self.macros
+            .insert(index, format!("#[{}]", attr.to_string()));
+        self
+    }
+
     /// Set an associated constant.
     pub fn associate_const<T>(
         &mut self,
@@ -101,6 +173,8 @@ impl Impl {
             annotation: Vec::new(),
             value: value.to_string(),
             visibility: Some(visibility.to_string()),
+            skip_hash: false,
+            group_comment: None,
         });
 
         self
@@ -118,11 +192,115 @@ impl Impl {
             annotation: Vec::new(),
             value: String::new(),
             visibility: None,
+            skip_hash: false,
+            group_comment: None,
+        });
+
+        self
+    }
+
+    /// Set an associated type with bounds, e.g., `type Foo: Bound = Concrete;`.
+    pub fn associate_type_with_bounds<T>(
+        &mut self,
+        name: impl ToString,
+        ty: T,
+        bounds: Vec<Type>,
+    ) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        let name = name.to_string();
+
+        self.associate_type(&name, ty);
+        self.assoc_ty_bounds.push(Bound {
+            name,
+            bound: bounds,
         });
 
         self
     }
 
+    /// Add a bound to the associated type with the given name.
+    ///
+    /// The bound is appended to any existing bounds of the associated type.
+    /// The associated type itself may be set with
+    /// [`associate_type`](#method.associate_type) before or after calling
+    /// this.
+    pub fn push_assoc_type_bound<T>(&mut self, name: &str, bound: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        match self.assoc_ty_bounds.iter_mut().find(|b| b.name == name) {
+            Some(existing) => existing.bound.push(bound.into()),
+            None => self.assoc_ty_bounds.push(Bound {
+                name: name.to_string(),
+                bound: vec![bound.into()],
+            }),
+        }
+
+        self
+    }
+
+    /// Implement a binary `std::ops` operator trait, e.g., `Add` or `BitAnd`.
+    ///
+    /// This sets the trait to `std::ops::Op<Rhs>`, the `Output` associated
+    /// type, and defines `fn op(self, rhs: Rhs) -> Output` with the provided
+    /// body. The method name is the lowercased operator name.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the impl block already implements a trait, or if `op` is not
+    /// one of the binary operators `Add`, `Sub`, `Mul`, `Div`, `Rem`,
+    /// `BitAnd`, `BitOr`, `BitXor`, `Shl` or `Shr`.
+    ///
+    /// ```
+    /// use codegen::Impl;
+    ///
+    /// let mut imp = Impl::new("Meters");
+    /// imp.add_op("Add", "Meters", "Meters", "Meters(self.0 + rhs.0)");
+    /// ```
+    pub fn add_op<T, U>(
+        &mut self,
+        op: impl ToString,
+        rhs: T,
+        output: U,
+        body: impl ToString,
+    ) -> &mut Self
+    where
+        T: Into<Type>,
+        U: Into<Type>,
+    {
+        const BINARY_OPS: &[&str] = &[
+            "Add", "Sub", "Mul", "Div", "Rem", "BitAnd", "BitOr", "BitXor", "Shl", "Shr",
+        ];
+
+        let op = op.to_string();
+        let rhs = rhs.into();
+
+        assert!(
+            BINARY_OPS.contains(&op.as_str()),
+            "`{}` is not a binary `std::ops` operator",
+            op
+        );
+        assert!(
+            self.impl_trait.is_none(),
+            "impl block already implements a trait"
+        );
+
+        let mut op_trait = Type::new(format!("std::ops::{}", op));
+        op_trait.generic(rhs.clone());
+
+        self.impl_trait(op_trait).associate_type("Output", output);
+
+        self.new_fn(op.to_lowercase())
+            .arg_self()
+            .arg("rhs", rhs)
+            .ret("Self::Output")
+            .line(body);
+
+        self
+    }
+
     /// Add a `where` bound to the impl block.
     pub fn bound<T>(&mut self, name: impl ToString, ty: T) -> &mut Self
     where
@@ -135,25 +313,98 @@ impl Impl {
         self
     }
 
+    /// Add a lifetime `where` bound to the impl block, e.g., `'a: 'b`.
+    pub fn lifetime_bound(&mut self, lt: impl ToString, bound_lt: impl ToString) -> &mut Self {
+        self.bounds.push(Bound::lifetime(lt, bound_lt));
+        self
+    }
+
     /// Push a new function definition, returning a mutable reference to it.
     pub fn new_fn(&mut self, name: impl ToString) -> &mut Function {
         self.push_fn(Function::new(name));
         self.fns.last_mut().unwrap()
     }
 
+    /// Push a new `const fn` definition, returning a mutable reference to it.
+    pub fn new_const_fn(&mut self, name: impl ToString) -> &mut Function {
+        let func = self.new_fn(name);
+        func.set_const(true);
+        func
+    }
+
+    /// Push a new `async fn` definition, returning a mutable reference to it.
+    pub fn new_async_fn(&mut self, name: impl ToString) -> &mut Function {
+        let func = self.new_fn(name);
+        func.set_async(true);
+        func
+    }
+
+    /// Push a new `unsafe fn` definition, returning a mutable reference to it.
+    pub fn new_unsafe_fn(&mut self, name: impl ToString) -> &mut Function {
+        let func = self.new_fn(name);
+        func.set_unsafe(true);
+        func
+    }
+
+    /// Push a new function whose body returns `Default::default()`, returning
+    /// a mutable reference to it.
+    ///
+    /// This is a starting point for generated trait impl stubs.
+    pub fn with_default_fn(&mut self, name: &str) -> &mut Function {
+        let func = self.new_fn(name);
+        func.line("Default::default()");
+        func
+    }
+
+    /// Push a new function whose body is `todo!()`, returning a mutable
+    /// reference to it.
+    ///
+    /// This is a starting point for generated trait impl stubs.
+    pub fn with_todo_fn(&mut self, name: &str) -> &mut Function {
+        let func = self.new_fn(name);
+        func.line("todo!()");
+        func
+    }
+
     /// Push a function definition.
     pub fn push_fn(&mut self, item: Function) -> &mut Self {
         self.fns.push(item);
         self
     }
 
+    /// Sort the functions of the impl block by the given key.
+    ///
+    /// The sort is stable, functions with equal keys keep their order.
+    pub fn sort_fns_by<K, F>(&mut self, mut key: F) -> &mut Self
+    where
+        K: Ord,
+        F: FnMut(&Function) -> K,
+    {
+        self.fns.sort_by_key(|func| key(func));
+        self
+    }
+
+    /// Sort the functions of the impl block by name.
+    pub fn sort_fns_alphabetically(&mut self) -> &mut Self {
+        self.fns.sort_by(|a, b| a.name().cmp(b.name()));
+        self
+    }
+
     /// Formats the impl block using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         for m in self.macros.iter() {
             writeln!(fmt, "{}", m)?;
         }
         write!(fmt, "impl")?;
-        fmt_generics(&self.generics[..], fmt)?;
+
+        let generics: Vec<String> = self
+            .lifetimes
+            .iter()
+            .chain(self.generics.iter())
+            .cloned()
+            .collect();
+        let (generics, bounds) = hoist_bounds(&generics, &self.bounds, fmt);
+        fmt_generics(&generics[..], fmt)?;
 
         if let Some(ref t) = self.impl_trait {
             write!(fmt, " ")?;
@@ -164,7 +415,7 @@ impl Impl {
         write!(fmt, " ")?;
         self.target.fmt(fmt)?;
 
-        fmt_bounds(&self.bounds, fmt)?;
+        fmt_bounds(&bounds, fmt)?;
 
         fmt.block(|fmt| {
             // format associated constants
@@ -182,7 +433,22 @@ impl Impl {
             // format associated types
             if !self.assoc_tys.is_empty() {
                 for ty in &self.assoc_tys {
-                    write!(fmt, "type {} = ", ty.name)?;
+                    write!(fmt, "type {}", ty.name)?;
+
+                    let bounds = self
+                        .assoc_ty_bounds
+                        .iter()
+                        .filter(|bound| bound.name == ty.name)
+                        .flat_map(|bound| bound.bound.iter())
+                        .cloned()
+                        .collect::<Vec<_>>();
+
+                    if !bounds.is_empty() {
+                        write!(fmt, ": ")?;
+                        fmt_bound_rhs(&bounds, fmt)?;
+                    }
+
+                    write!(fmt, " = ")?;
                     ty.ty.fmt(fmt)?;
                     writeln!(fmt, ";")?;
                 }
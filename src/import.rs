@@ -3,6 +3,12 @@
 pub struct Import {
     /// Function visibility
     pub vis: Option<String>,
+
+    /// Name the import is bound to, if different from the imported name
+    pub alias: Option<String>,
+
+    /// Condition of a `#[cfg(...)]` attribute applied to the import
+    pub cfg: Option<String>,
 }
 
 impl Default for Import {
@@ -14,7 +20,19 @@ impl Default for Import {
 impl Import {
     /// Return a new import.
     pub fn new() -> Self {
-        Import { vis: None }
+        Import {
+            vis: None,
+            alias: None,
+            cfg: None,
+        }
+    }
+
+    /// Set the name the import is bound to, i.e., `use path::Type as Alias;`.
+    ///
+    /// An alias equal to the imported name is omitted.
+    pub fn alias(&mut self, alias: impl ToString) -> &mut Self {
+        self.alias = Some(alias.to_string());
+        self
     }
 
     /// Set the import visibility.
@@ -22,4 +40,69 @@ impl Import {
         self.vis = Some(vis.to_string());
         self
     }
+
+    /// Only import when the given condition holds, i.e.,
+    /// `#[cfg(condition)]`.
+    pub fn cfg(&mut self, condition: impl ToString) -> &mut Self {
+        self.cfg = Some(condition.to_string());
+        self
+    }
+}
+
+/// Defines a group of types imported from the same path, sharing a
+/// visibility and `cfg` condition.
+///
+/// ```
+/// use codegen::{ImportGroup, Scope};
+///
+/// let mut group = ImportGroup::new("std::collections");
+/// group.ty("HashMap").ty("HashSet").cfg("feature = \"std\"");
+///
+/// let mut scope = Scope::new();
+/// scope.import_group(group);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ImportGroup {
+    /// Path the types are imported from
+    pub path: String,
+
+    /// Types imported from the path
+    pub tys: Vec<String>,
+
+    /// Visibility of the imports
+    pub vis: Option<String>,
+
+    /// Condition of a `#[cfg(...)]` attribute applied to the imports
+    pub cfg: Option<String>,
+}
+
+impl ImportGroup {
+    /// Return a new, empty import group for the given path.
+    pub fn new(path: impl ToString) -> Self {
+        ImportGroup {
+            path: path.to_string(),
+            tys: Vec::new(),
+            vis: None,
+            cfg: None,
+        }
+    }
+
+    /// Add a type to the group.
+    pub fn ty(&mut self, ty: impl ToString) -> &mut Self {
+        self.tys.push(ty.to_string());
+        self
+    }
+
+    /// Set the visibility of the imports.
+    pub fn vis(&mut self, vis: impl ToString) -> &mut Self {
+        self.vis = Some(vis.to_string());
+        self
+    }
+
+    /// Only import when the given condition holds, i.e.,
+    /// `#[cfg(condition)]`.
+    pub fn cfg(&mut self, condition: impl ToString) -> &mut Self {
+        self.cfg = Some(condition.to_string());
+        self
+    }
 }
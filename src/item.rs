@@ -1,3 +1,9 @@
+use std::fmt::{self, Write};
+
+use crate::block::Block;
+use crate::cfg_if::CfgIf;
+use crate::code_block::CodeBlock;
+use crate::formatter::Formatter;
 use crate::function::Function;
 use crate::module::Module;
 use crate::r#const::Const;
@@ -6,16 +12,78 @@ use crate::r#impl::Impl;
 use crate::r#struct::Struct;
 use crate::r#trait::Trait;
 use crate::r#type_alias::TypeAlias;
+#[cfg(feature = "nightly")]
+use crate::trait_alias::TraitAlias;
 
+/// An item defined in a scope.
 #[derive(Debug, Clone)]
 pub enum Item {
+    /// A module definition
     Module(Module),
+    /// A struct definition
     Struct(Struct),
+    /// A function definition
     Function(Function),
+    /// A trait definition
     Trait(Trait),
+    /// An enum definition
     Enum(Enum),
+    /// An `impl` block
     Impl(Impl),
+    /// A raw string, included verbatim
     Raw(String),
+    /// A re-indented block of arbitrary code
+    CodeBlock(CodeBlock),
+    /// A type alias
     TypeAlias(TypeAlias),
+    /// A const
     Const(Const),
+    /// An unnamed `const _: () = { ... };` block
+    ConstBlock(Block),
+    /// A `cfg_if::cfg_if!` invocation
+    CfgIf(CfgIf),
+    /// A trait alias
+    #[cfg(feature = "nightly")]
+    TraitAlias(TraitAlias),
+}
+
+impl Item {
+    /// Returns the name of the item, if it has one.
+    ///
+    /// The name of an `impl` block is the name of its target type.
+    pub fn name(&self) -> Option<&str> {
+        match *self {
+            Item::Module(ref v) => Some(&v.name),
+            Item::Struct(ref v) => Some(v.ty().name()),
+            Item::Function(ref v) => Some(v.name()),
+            Item::Trait(ref v) => Some(v.ty().name()),
+            Item::Enum(ref v) => Some(v.ty().name()),
+            Item::Impl(ref v) => Some(v.target_name()),
+            Item::TypeAlias(ref v) => Some(v.type_def().name()),
+            Item::Const(ref v) => Some(v.get_name()),
+            #[cfg(feature = "nightly")]
+            Item::TraitAlias(ref v) => Some(v.ty().name()),
+            Item::Raw(_) | Item::CodeBlock(_) | Item::ConstBlock(_) | Item::CfgIf(_) => None,
+        }
+    }
+
+    /// Formats the item using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Item::Module(ref v) => v.fmt(fmt),
+            Item::Struct(ref v) => v.fmt(fmt),
+            Item::Function(ref v) => v.fmt(false, fmt),
+            Item::Trait(ref v) => v.fmt(fmt),
+            Item::Enum(ref v) => v.fmt(fmt),
+            Item::Impl(ref v) => v.fmt(fmt),
+            Item::Raw(ref v) => writeln!(fmt, "{}", v),
+            Item::CodeBlock(ref v) => v.fmt(fmt),
+            Item::TypeAlias(ref v) => v.fmt(fmt),
+            Item::Const(ref v) => v.fmt(fmt),
+            Item::ConstBlock(ref v) => v.fmt(fmt),
+            Item::CfgIf(ref v) => v.fmt(fmt),
+            #[cfg(feature = "nightly")]
+            Item::TraitAlias(ref v) => v.fmt(fmt),
+        }
+    }
 }
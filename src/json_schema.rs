@@ -0,0 +1,103 @@
+use serde_json::{json, Value};
+
+use crate::formatter::Formatter;
+
+use crate::r#type::Type;
+
+/// Returns the JSON schema describing values of the given type.
+///
+/// Types without a JSON equivalent are referenced by name, i.e.,
+/// `{"$ref": "#/$defs/Name"}`.
+pub fn type_schema(ty: &Type) -> Value {
+    let mut ty_str = String::new();
+    ty.fmt(&mut Formatter::new(&mut ty_str)).unwrap();
+
+    schema_for(&ty_str)
+}
+
+/// Returns whether a field of the given type may be omitted, i.e., is an
+/// `Option`.
+pub fn is_optional(ty: &Type) -> bool {
+    base_name(ty.name()) == "Option"
+}
+
+fn schema_for(ty: &str) -> Value {
+    let ty = strip_reference(ty.trim());
+
+    // Slices and arrays, `[T]` and `[T; N]`
+    if let Some(inner) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+        let elem = inner.split(';').next().unwrap_or(inner);
+        return json!({ "type": "array", "items": schema_for(elem) });
+    }
+
+    let (base, args) = match ty.find('<') {
+        Some(i) if ty.ends_with('>') => (&ty[..i], split_args(&ty[i + 1..ty.len() - 1])),
+        _ => (ty, vec![]),
+    };
+
+    match (base_name(base), &args[..]) {
+        ("bool", _) => json!({ "type": "boolean" }),
+        (
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+            | "usize",
+            _,
+        ) => json!({ "type": "integer" }),
+        ("f32" | "f64", _) => json!({ "type": "number" }),
+        ("String" | "str" | "char", _) => json!({ "type": "string" }),
+        ("()", _) => json!({ "type": "null" }),
+        ("Option" | "Box" | "Rc" | "Arc" | "Cow", [.., inner]) => schema_for(inner),
+        ("Vec" | "VecDeque" | "HashSet" | "BTreeSet", [inner]) => {
+            json!({ "type": "array", "items": schema_for(inner) })
+        }
+        ("HashMap" | "BTreeMap", [_, value]) => {
+            json!({ "type": "object", "additionalProperties": schema_for(value) })
+        }
+        (name, _) => json!({ "$ref": format!("#/$defs/{}", name) }),
+    }
+}
+
+/// Strip a leading `&`, `&'a` or `&mut` from a type.
+fn strip_reference(ty: &str) -> &str {
+    let Some(ty) = ty.strip_prefix('&') else {
+        return ty;
+    };
+
+    let ty = ty.trim_start();
+    let ty = match ty.strip_prefix('\'') {
+        Some(rest) => rest.split_once(' ').map_or("", |(_, ty)| ty),
+        None => ty,
+    };
+
+    strip_reference(ty.trim_start().trim_start_matches("mut ").trim_start())
+}
+
+/// Returns the last path segment of a type name, without generics.
+fn base_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name).trim()
+}
+
+/// Split generic arguments on top-level commas.
+fn split_args(args: &str) -> Vec<&str> {
+    let mut ret = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in args.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                ret.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if !args[start..].trim().is_empty() {
+        ret.push(args[start..].trim());
+    }
+
+    ret
+}
@@ -6,12 +6,12 @@
 //!
 //! 1. Create a `Scope` instance.
 //! 2. Use the builder API to add elements to the scope.
-//! 3. Call `Scope::to_string()` to get the generated code.
+//! 3. Call `Scope::fmt_with_config()` to get the generated code.
 //!
 //! For example:
 //!
 //! ```rust
-//! use codegen::Scope;
+//! use codegen::{FormatterConfig, Scope};
 //!
 //! let mut scope = Scope::new();
 //!
@@ -20,7 +20,7 @@
 //!     .field("one", "usize")
 //!     .field("two", "String");
 //!
-//! println!("{}", scope.to_string());
+//! println!("{}", scope.fmt_with_config(&FormatterConfig::default()));
 //! ```
 
 mod associated_const;
@@ -28,6 +28,8 @@ mod associated_type;
 mod block;
 mod body;
 mod bound;
+mod cfg_if;
+mod code_block;
 mod docs;
 mod field;
 mod fields;
@@ -35,6 +37,8 @@ mod formatter;
 mod function;
 mod import;
 mod item;
+#[cfg(feature = "json-schema")]
+mod json_schema;
 mod module;
 mod scope;
 mod type_def;
@@ -45,22 +49,31 @@ mod r#enum;
 mod r#impl;
 mod r#struct;
 mod r#trait;
+#[cfg(feature = "nightly")]
+mod trait_alias;
 mod r#type;
 mod type_alias;
 
 pub use associated_const::*;
 pub use associated_type::*;
 pub use block::*;
+pub use cfg_if::*;
+pub use code_block::*;
 pub use field::*;
 pub use formatter::*;
 pub use function::*;
 pub use import::*;
+pub use item::*;
 pub use module::*;
 pub use scope::*;
 pub use variant::*;
 
+pub use r#const::*;
 pub use r#enum::*;
 pub use r#impl::*;
 pub use r#struct::*;
 pub use r#trait::*;
 pub use r#type::*;
+#[cfg(feature = "nightly")]
+pub use trait_alias::*;
+pub use type_alias::*;
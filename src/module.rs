@@ -3,8 +3,10 @@ use std::fmt::{self, Display, Write};
 use crate::docs::Docs;
 use crate::formatter::Formatter;
 use crate::function::Function;
+use crate::import::{Import, ImportGroup};
 use crate::scope::Scope;
 
+use crate::r#const::Const;
 use crate::r#enum::Enum;
 use crate::r#impl::Impl;
 use crate::r#struct::Struct;
@@ -24,10 +26,13 @@ pub struct Module {
     docs: Option<Docs>,
 
     /// Contents of the module
-    scope: Scope,
+    pub(crate) scope: Scope,
 
     /// Module attributes, e.g., `#[allow(unused_imports)]`.
     attributes: Vec<String>,
+
+    /// Whether items may no longer be added to the module
+    sealed: bool,
 }
 
 impl Module {
@@ -39,9 +44,36 @@ impl Module {
             docs: None,
             scope: Scope::new(),
             attributes: Vec::new(),
+            sealed: false,
         }
     }
 
+    /// Rename the module.
+    pub fn rename(&mut self, new_name: impl ToString) -> &mut Self {
+        self.name = new_name.to_string();
+        self
+    }
+
+    /// Seal the module, preventing further items from being added.
+    ///
+    /// Calling any of the `new_*`, `push_*` or `import*` functions, or
+    /// [`scope`](#method.scope) or [`attr_inner`](#method.attr_inner), on a
+    /// sealed module panics. Items can still be modified in place, e.g., via
+    /// the `*_mut` functions.
+    pub fn seal(&mut self) -> &mut Self {
+        self.sealed = true;
+        self
+    }
+
+    /// Returns `true` if the module is sealed.
+    pub fn is_sealed(&self) -> bool {
+        self.sealed
+    }
+
+    fn assert_unsealed(&self) {
+        assert!(!self.sealed, "module `{}` is sealed", self.name);
+    }
+
     /// Set the module documentation.
     pub fn doc(&mut self, docs: impl ToString) -> &mut Self {
         self.docs = Some(Docs::new(docs));
@@ -49,7 +81,12 @@ impl Module {
     }
 
     /// Returns a mutable reference to the module's scope.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the module is sealed.
     pub fn scope(&mut self) -> &mut Scope {
+        self.assert_unsealed();
         &mut self.scope
     }
 
@@ -63,8 +100,22 @@ impl Module {
     ///
     /// This results in a new `use` statement bein added to the beginning of the
     /// module.
-    pub fn import(&mut self, path: impl ToString, ty: impl ToString) -> &mut Self {
-        self.scope.import(path, ty);
+    pub fn import(&mut self, path: impl ToString, ty: impl ToString) -> &mut Import {
+        self.assert_unsealed();
+        self.scope.import(path, ty)
+    }
+
+    /// Import all public items of a path into the module's scope, i.e.,
+    /// `use path::*;`.
+    pub fn import_glob(&mut self, path: impl ToString) -> &mut Import {
+        self.assert_unsealed();
+        self.scope.import_glob(path)
+    }
+
+    /// Import all types of an import group into the module's scope.
+    pub fn import_group(&mut self, group: ImportGroup) -> &mut Self {
+        self.assert_unsealed();
+        self.scope.import_group(group);
         self
     }
 
@@ -74,6 +125,25 @@ impl Module {
         self
     }
 
+    /// Insert an attribute at the given position among the module's
+    /// attributes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of attributes.
+    pub fn attribute_at(&mut self, index: usize, attr: impl ToString) -> &mut Self {
+        self.attributes.insert(index, attr.to_string());
+        self
+    }
+
+    /// Add an inner attribute to the module, emitted as `#![attr]` at the
+    /// start of the module body.
+    pub fn attr_inner(&mut self, attribute: impl ToString) -> &mut Self {
+        self.assert_unsealed();
+        self.scope.attr_inner(attribute);
+        self
+    }
+
     /// Push a new module definition, returning a mutable reference to it.
     ///
     /// # Panics
@@ -87,6 +157,7 @@ impl Module {
     ///
     /// [`get_or_new_module`]: #method.get_or_new_module
     pub fn new_module(&mut self, name: impl ToString) -> &mut Module {
+        self.assert_unsealed();
         self.scope.new_module(name)
     }
 
@@ -106,12 +177,93 @@ impl Module {
         self.scope.get_module_mut(name)
     }
 
+    /// Returns an iterator over the structs defined directly in the module.
+    pub fn structs(&self) -> impl Iterator<Item = &Struct> {
+        self.scope.structs()
+    }
+
+    /// Returns a mutable iterator over the structs defined directly in the
+    /// module.
+    pub fn structs_mut(&mut self) -> impl Iterator<Item = &mut Struct> {
+        self.scope.structs_mut()
+    }
+
+    /// Returns an iterator over the enums defined directly in the module.
+    pub fn enums(&self) -> impl Iterator<Item = &Enum> {
+        self.scope.enums()
+    }
+
+    /// Returns a mutable iterator over the enums defined directly in the
+    /// module.
+    pub fn enums_mut(&mut self) -> impl Iterator<Item = &mut Enum> {
+        self.scope.enums_mut()
+    }
+
+    /// Returns an iterator over the traits defined directly in the module.
+    pub fn traits(&self) -> impl Iterator<Item = &Trait> {
+        self.scope.traits()
+    }
+
+    /// Returns a mutable iterator over the traits defined directly in the
+    /// module.
+    pub fn traits_mut(&mut self) -> impl Iterator<Item = &mut Trait> {
+        self.scope.traits_mut()
+    }
+
+    /// Returns an iterator over the functions defined directly in the module.
+    pub fn fns(&self) -> impl Iterator<Item = &Function> {
+        self.scope.fns()
+    }
+
+    /// Returns a mutable iterator over the functions defined directly in the
+    /// module.
+    pub fn fns_mut(&mut self) -> impl Iterator<Item = &mut Function> {
+        self.scope.fns_mut()
+    }
+
+    /// Returns an iterator over the `impl` blocks defined directly in the module.
+    pub fn impls(&self) -> impl Iterator<Item = &Impl> {
+        self.scope.impls()
+    }
+
+    /// Returns a mutable iterator over the `impl` blocks defined directly in the
+    /// module.
+    pub fn impls_mut(&mut self) -> impl Iterator<Item = &mut Impl> {
+        self.scope.impls_mut()
+    }
+
+    /// Returns an iterator over the consts defined directly in the module.
+    pub fn consts(&self) -> impl Iterator<Item = &Const> {
+        self.scope.consts()
+    }
+
+    /// Returns a mutable iterator over the consts defined directly in the
+    /// module.
+    pub fn consts_mut(&mut self) -> impl Iterator<Item = &mut Const> {
+        self.scope.consts_mut()
+    }
+
+    /// Returns an iterator over the modules defined directly in the module.
+    pub fn modules(&self) -> impl Iterator<Item = &Module> {
+        self.scope.modules()
+    }
+
+    /// Returns a mutable iterator over the modules defined directly in the
+    /// module.
+    pub fn modules_mut(&mut self) -> impl Iterator<Item = &mut Module> {
+        self.scope.modules_mut()
+    }
+
     /// Returns a mutable reference to a module, creating it if it does
     /// not exist.
     pub fn get_or_new_module<Q: ?Sized + Display>(&mut self, name: &Q) -> &mut Module
     where
         String: PartialEq<Q>,
     {
+        if self.get_module(name).is_none() {
+            self.assert_unsealed();
+        }
+
         self.scope.get_or_new_module(name)
     }
 
@@ -128,72 +280,85 @@ impl Module {
     ///
     /// [`get_or_new_module`]: #method.get_or_new_module
     pub fn push_module(&mut self, item: Module) -> &mut Self {
+        self.assert_unsealed();
         self.scope.push_module(item);
         self
     }
 
     /// Push a new struct definition, returning a mutable reference to it.
     pub fn new_struct(&mut self, name: impl ToString) -> &mut Struct {
+        self.assert_unsealed();
         self.scope.new_struct(name)
     }
 
     /// Push a structure definition
     pub fn push_struct(&mut self, item: Struct) -> &mut Self {
+        self.assert_unsealed();
         self.scope.push_struct(item);
         self
     }
 
     /// Push a new `TypeAlias`, returning a mutable reference to it.
     pub fn new_type_alias(&mut self, name: impl ToString, target: impl ToString) -> &mut TypeAlias {
+        self.assert_unsealed();
         self.scope.new_type_alias(name, target)
     }
 
     /// Push an `TypeAlias`.
     pub fn push_type_alias(&mut self, item: TypeAlias) -> &mut Self {
+        self.assert_unsealed();
         self.scope.push_type_alias(item);
         self
     }
 
     /// Push a new function definition, returning a mutable reference to it.
     pub fn new_fn(&mut self, name: impl ToString) -> &mut Function {
+        self.assert_unsealed();
         self.scope.new_fn(name)
     }
 
     /// Push a function definition
     pub fn push_fn(&mut self, item: Function) -> &mut Self {
+        self.assert_unsealed();
         self.scope.push_fn(item);
         self
     }
 
     /// Push a new enum definition, returning a mutable reference to it.
     pub fn new_enum(&mut self, name: impl ToString) -> &mut Enum {
+        self.assert_unsealed();
         self.scope.new_enum(name)
     }
 
     /// Push an enum definition
     pub fn push_enum(&mut self, item: Enum) -> &mut Self {
+        self.assert_unsealed();
         self.scope.push_enum(item);
         self
     }
 
     /// Push a new `impl` block, returning a mutable reference to it.
     pub fn new_impl(&mut self, target: impl ToString) -> &mut Impl {
+        self.assert_unsealed();
         self.scope.new_impl(target)
     }
 
     /// Push an `impl` block.
     pub fn push_impl(&mut self, item: Impl) -> &mut Self {
+        self.assert_unsealed();
         self.scope.push_impl(item);
         self
     }
 
     /// Push a new trait
     pub fn new_trait(&mut self, name: impl ToString) -> &mut Trait {
+        self.assert_unsealed();
         self.scope.new_trait(name)
     }
 
     /// Push a trait definition
     pub fn push_trait(&mut self, item: Trait) -> &mut Self {
+        self.assert_unsealed();
         self.scope.push_trait(item);
         self
     }
@@ -204,15 +369,13 @@ impl Module {
             docs.fmt(fmt)?;
         }
 
-        for attr in &self.attributes {
-            writeln!(fmt, "#[{}] ", attr)?;
-        }
+        fmt.emit_attributes(&self.attributes)?;
 
         if let Some(ref vis) = self.vis {
             write!(fmt, "{} ", vis)?;
         }
 
         write!(fmt, "mod {}", self.name)?;
-        fmt.block(|fmt| self.scope.fmt(fmt))
+        fmt.block(|fmt| self.scope.fmt_nested(fmt))
     }
 }
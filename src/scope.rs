@@ -1,11 +1,17 @@
 use std::fmt::{self, Debug, Display, Write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 use indexmap::IndexMap;
 
+use crate::block::Block;
+use crate::cfg_if::CfgIf;
+use crate::code_block::CodeBlock;
 use crate::docs::Docs;
-use crate::formatter::Formatter;
+use crate::formatter::{Formatter, FormatterConfig, RustEdition};
 use crate::function::Function;
-use crate::import::Import;
+use crate::import::{Import, ImportGroup};
 use crate::item::Item;
 use crate::module::Module;
 use crate::r#const::Const;
@@ -13,6 +19,8 @@ use crate::r#enum::Enum;
 use crate::r#impl::Impl;
 use crate::r#struct::Struct;
 use crate::r#trait::Trait;
+#[cfg(feature = "nightly")]
+use crate::trait_alias::TraitAlias;
 use crate::type_alias::TypeAlias;
 
 /// Defines a scope.
@@ -23,6 +31,12 @@ pub struct Scope {
     /// Scope documentation
     docs: Option<Docs>,
 
+    /// Nightly features, emitted as a single `#![feature(...)]`.
+    features: Vec<String>,
+
+    /// Inner attributes, e.g., `#![recursion_limit = "256"]`.
+    inner_attributes: Vec<String>,
+
     /// Imports
     imports: IndexMap<String, IndexMap<String, Import>>,
 
@@ -30,6 +44,109 @@ pub struct Scope {
     items: Vec<Item>,
 }
 
+/// Counts of each kind of item defined directly in a scope.
+///
+/// Items nested inside modules are not included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScopeSummary {
+    /// Number of struct definitions
+    pub structs: usize,
+
+    /// Number of enum definitions
+    pub enums: usize,
+
+    /// Number of trait definitions, including trait aliases
+    pub traits: usize,
+
+    /// Number of `impl` blocks
+    pub impls: usize,
+
+    /// Number of function definitions
+    pub fns: usize,
+
+    /// Number of module definitions
+    pub modules: usize,
+
+    /// Number of consts, including unnamed const blocks
+    pub consts: usize,
+
+    /// Number of type aliases
+    pub type_aliases: usize,
+
+    /// Number of raw strings
+    pub raw_items: usize,
+
+    /// Number of code blocks
+    pub code_blocks: usize,
+
+    /// Number of `cfg_if!` invocations
+    pub cfg_ifs: usize,
+}
+
+/// Options used by [`Scope::write_to_dir`].
+#[derive(Debug, Clone, Default)]
+pub struct WriteConfig {
+    /// The Rust edition targeted by the generated code.
+    pub rust_edition: RustEdition,
+
+    /// Whether to run `rustfmt` on the written file.
+    pub rustfmt: bool,
+}
+
+/// Defines the `get_*`, `get_*_mut` and `remove_*` functions of a kind of
+/// item, which is looked up by the name returned by the closure.
+macro_rules! item_accessors {
+    (
+        $variant:ident,
+        $desc:literal,
+        $get:ident,
+        $get_mut:ident,
+        $remove:ident,
+        |$v:ident| $name:expr
+    ) => {
+        #[doc = concat!(
+            "Returns a reference to ", $desc, ", if it exists in this scope."
+        )]
+        pub fn $get<Q: ?Sized>(&self, name: &Q) -> Option<&$variant>
+        where
+            String: PartialEq<Q>,
+        {
+            self.items.iter().find_map(|item| match item {
+                Item::$variant($v) if name_eq($name, name) => Some($v),
+                _ => None,
+            })
+        }
+
+        #[doc = concat!(
+            "Returns a mutable reference to ", $desc, ", if it exists in this scope."
+        )]
+        pub fn $get_mut<Q: ?Sized>(&mut self, name: &Q) -> Option<&mut $variant>
+        where
+            String: PartialEq<Q>,
+        {
+            self.items.iter_mut().find_map(|item| match item {
+                Item::$variant($v) if name_eq($name, name) => Some($v),
+                _ => None,
+            })
+        }
+
+        #[doc = concat!("Remove ", $desc, " from the scope, returning it.")]
+        pub fn $remove<Q: ?Sized>(&mut self, name: &Q) -> Option<$variant>
+        where
+            String: PartialEq<Q>,
+        {
+            let pos = self.items.iter().position(|item| {
+                matches!(item, Item::$variant($v) if name_eq($name, name))
+            })?;
+
+            match self.items.remove(pos) {
+                Item::$variant(v) => Some(v),
+                _ => unreachable!(),
+            }
+        }
+    };
+}
+
 impl Default for Scope {
     fn default() -> Self {
         Self::new()
@@ -41,6 +158,8 @@ impl Scope {
     pub fn new() -> Self {
         Scope {
             docs: None,
+            features: vec![],
+            inner_attributes: vec![],
             imports: IndexMap::new(),
             items: vec![],
         }
@@ -52,6 +171,59 @@ impl Scope {
         self
     }
 
+    /// Add an inner attribute to the scope, emitted as `#![attr]` before
+    /// anything else in the scope.
+    pub fn attr_inner(&mut self, attribute: impl ToString) -> &mut Self {
+        self.inner_attributes.push(attribute.to_string());
+        self
+    }
+
+    /// Enable a nightly feature, e.g., `"never_type"`.
+    ///
+    /// All features of the scope are emitted in a single
+    /// `#![feature(a, b)]` inner attribute. Enabling a feature twice has no
+    /// effect.
+    ///
+    /// Features only have an effect at the crate root, so features enabled
+    /// on the scope of a module are hoisted to the scope being formatted.
+    pub fn feature(&mut self, name: impl ToString) -> &mut Self {
+        let name = name.to_string();
+
+        if !self.features.contains(&name) {
+            self.features.push(name);
+        }
+
+        self
+    }
+
+    /// Set the `#![recursion_limit = "N"]` inner attribute, replacing any
+    /// previously set limit.
+    pub fn recursion_limit(&mut self, n: usize) -> &mut Self {
+        self.set_inner_attr("recursion_limit", n)
+    }
+
+    /// Set the `#![type_length_limit = "N"]` inner attribute, replacing any
+    /// previously set limit.
+    pub fn type_length_limit(&mut self, n: usize) -> &mut Self {
+        self.set_inner_attr("type_length_limit", n)
+    }
+
+    fn set_inner_attr(&mut self, name: &str, value: impl fmt::Display) -> &mut Self {
+        let attr = format!("{} = \"{}\"", name, value);
+
+        let existing = self.inner_attributes.iter_mut().find(|attr| {
+            attr.strip_prefix(name)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        });
+
+        match existing {
+            Some(existing) => *existing = attr,
+            None => self.inner_attributes.push(attr),
+        }
+
+        self
+    }
+
     /// Import a type into the scope.
     ///
     /// This results in a new `use` statement being added to the beginning of
@@ -68,6 +240,120 @@ impl Scope {
             .or_default()
     }
 
+    /// Import all types of an import group into the scope.
+    ///
+    /// The visibility and `cfg` condition of the group, if any, are applied
+    /// to each import.
+    pub fn import_group(&mut self, group: ImportGroup) -> &mut Self {
+        for ty in &group.tys {
+            let import = self.import(&group.path, ty);
+
+            if let Some(ref vis) = group.vis {
+                import.vis(vis);
+            }
+
+            if let Some(ref cfg) = group.cfg {
+                import.cfg(cfg);
+            }
+        }
+
+        self
+    }
+
+    /// Remove an import from the scope, returning whether it was present.
+    ///
+    /// The path and type are interpreted as in [`import`](#method.import).
+    /// The path is removed once none of its types are imported anymore.
+    pub fn remove_import(&mut self, path: &str, ty: &str) -> bool {
+        let ty = ty.split("::").next().unwrap_or(ty);
+
+        let imports = match self.imports.get_mut(path) {
+            Some(imports) => imports,
+            None => return false,
+        };
+
+        let removed = imports.shift_remove(ty).is_some();
+
+        if imports.is_empty() {
+            self.imports.shift_remove(path);
+        }
+
+        removed
+    }
+
+    /// Returns a reference to an import if it exists in this scope.
+    ///
+    /// The path and type are interpreted as in [`import`](#method.import).
+    pub fn get_import(&self, path: &str, ty: &str) -> Option<&Import> {
+        let ty = ty.split("::").next().unwrap_or(ty);
+
+        self.imports.get(path)?.get(ty)
+    }
+
+    /// Returns a mutable reference to an import if it exists in this scope.
+    ///
+    /// The path and type are interpreted as in [`import`](#method.import).
+    pub fn get_import_mut(&mut self, path: &str, ty: &str) -> Option<&mut Import> {
+        let ty = ty.split("::").next().unwrap_or(ty);
+
+        self.imports.get_mut(path)?.get_mut(ty)
+    }
+
+    /// Returns `true` if the scope imports the given type.
+    ///
+    /// The path and type are interpreted as in [`import`](#method.import).
+    pub fn has_import(&self, path: &str, ty: &str) -> bool {
+        self.get_import(path, ty).is_some()
+    }
+
+    /// Import all public items of a path into the scope, i.e., `use path::*;`.
+    ///
+    /// Glob imports are emitted on their own line, even when types are also
+    /// imported from the same path, in which case the glob import follows
+    /// them.
+    pub fn import_glob(&mut self, path: impl ToString) -> &mut Import {
+        self.import(path, "*")
+    }
+
+    /// Import a type into the scope with the given visibility.
+    ///
+    /// This is a shorthand for `scope.import(path, ty).vis(vis)`.
+    pub fn import_as_vis(
+        &mut self,
+        path: impl ToString,
+        ty: impl ToString,
+        vis: impl ToString,
+    ) -> &mut Import {
+        self.import(path, ty).vis(vis)
+    }
+
+    /// Publicly re-export a type from the scope, i.e., `pub use path::Type;`.
+    pub fn pub_import(&mut self, path: impl ToString, ty: impl ToString) -> &mut Import {
+        self.import_as_vis(path, ty, "pub")
+    }
+
+    /// Import a module path itself into the scope.
+    ///
+    /// When types are also imported from the same path, this results in a
+    /// `use path::{self, TypeA};` statement. Otherwise, `use path;` is
+    /// emitted.
+    pub fn import_self(&mut self, path: impl ToString) -> &mut Import {
+        self.import(path, "self")
+    }
+
+    /// Import a type into the scope under a different name.
+    ///
+    /// This results in a `use path::Type as Alias;` statement being added to
+    /// the beginning of the scope.
+    pub fn import_as(
+        &mut self,
+        path: impl ToString,
+        ty: impl ToString,
+        alias: impl ToString,
+    ) -> &mut Import {
+        self.import(path, ty).alias(alias)
+    }
+
     /// Push a new module definition, returning a mutable reference to it.
     ///
     /// # Panics
@@ -117,6 +403,213 @@ impl Scope {
             .next()
     }
 
+    item_accessors!(
+        Struct,
+        "the first struct with the given name",
+        get_struct,
+        get_struct_mut,
+        remove_struct,
+        |v| v.ty().name()
+    );
+
+    item_accessors!(
+        Enum,
+        "the first enum with the given name",
+        get_enum,
+        get_enum_mut,
+        remove_enum,
+        |v| v.ty().name()
+    );
+
+    item_accessors!(
+        Trait,
+        "the first trait with the given name",
+        get_trait,
+        get_trait_mut,
+        remove_trait,
+        |v| v.ty().name()
+    );
+
+    item_accessors!(
+        Function,
+        "the first function with the given name",
+        get_fn,
+        get_fn_mut,
+        remove_fn,
+        |v| v.name()
+    );
+
+    item_accessors!(
+        Impl,
+        "the first `impl` block whose target type has the given name",
+        get_impl,
+        get_impl_mut,
+        remove_impl,
+        |v| v.target_name()
+    );
+
+    item_accessors!(
+        Const,
+        "the first const with the given name",
+        get_const,
+        get_const_mut,
+        remove_const,
+        |v| v.get_name()
+    );
+
+    item_accessors!(
+        TypeAlias,
+        "the first type alias with the given name",
+        get_type_alias,
+        get_type_alias_mut,
+        remove_type_alias,
+        |v| v.type_def().name()
+    );
+
+    /// Returns an iterator over the structs defined directly in the scope.
+    pub fn structs(&self) -> impl Iterator<Item = &Struct> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Struct(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable iterator over the structs defined directly in the
+    /// scope.
+    pub fn structs_mut(&mut self) -> impl Iterator<Item = &mut Struct> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Struct(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the enums defined directly in the scope.
+    pub fn enums(&self) -> impl Iterator<Item = &Enum> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Enum(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable iterator over the enums defined directly in the
+    /// scope.
+    pub fn enums_mut(&mut self) -> impl Iterator<Item = &mut Enum> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Enum(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the traits defined directly in the scope.
+    pub fn traits(&self) -> impl Iterator<Item = &Trait> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Trait(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable iterator over the traits defined directly in the
+    /// scope.
+    pub fn traits_mut(&mut self) -> impl Iterator<Item = &mut Trait> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Trait(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the functions defined directly in the scope.
+    pub fn fns(&self) -> impl Iterator<Item = &Function> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Function(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable iterator over the functions defined directly in the
+    /// scope.
+    pub fn fns_mut(&mut self) -> impl Iterator<Item = &mut Function> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Function(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the `impl` blocks defined directly in the scope.
+    pub fn impls(&self) -> impl Iterator<Item = &Impl> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Impl(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable iterator over the `impl` blocks defined directly in the
+    /// scope.
+    pub fn impls_mut(&mut self) -> impl Iterator<Item = &mut Impl> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Impl(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the consts defined directly in the scope.
+    pub fn consts(&self) -> impl Iterator<Item = &Const> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Const(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable iterator over the consts defined directly in the
+    /// scope.
+    pub fn consts_mut(&mut self) -> impl Iterator<Item = &mut Const> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Const(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the modules defined directly in the scope.
+    pub fn modules(&self) -> impl Iterator<Item = &Module> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Module(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Returns a mutable iterator over the modules defined directly in the
+    /// scope.
+    pub fn modules_mut(&mut self) -> impl Iterator<Item = &mut Module> {
+        self.items.iter_mut().filter_map(|item| match item {
+            Item::Module(v) => Some(v),
+            _ => None,
+        })
+    }
+
+    /// Replace the first item with the given name, returning the replaced
+    /// item.
+    ///
+    /// The new item takes the position of the replaced item. If no item has
+    /// the given name, the scope is left unchanged and `None` is returned.
+    /// See [`Item::name`] for how items are named.
+    pub fn replace_item(&mut self, name: &str, new_item: Item) -> Option<Item> {
+        let item = self
+            .items
+            .iter_mut()
+            .find(|item| item.name() == Some(name))?;
+
+        Some(std::mem::replace(item, new_item))
+    }
+
+    /// Insert an item at the given position among the scope's items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of items.
+    pub fn insert_at(&mut self, index: usize, item: Item) -> &mut Self {
+        self.items.insert(index, item);
+        self
+    }
+
     /// Returns a mutable reference to a module, creating it if it does
     /// not exist.
     pub fn get_or_new_module<Q: ?Sized + Display>(&mut self, name: &Q) -> &mut Module
@@ -244,6 +737,64 @@ impl Scope {
         self
     }
 
+    /// Push a new `cfg_if::cfg_if!` invocation, returning a mutable reference
+    /// to it.
+    pub fn new_cfg_if(&mut self) -> &mut CfgIf {
+        self.push_cfg_if(CfgIf::new());
+
+        match *self.items.last_mut().unwrap() {
+            Item::CfgIf(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a `cfg_if::cfg_if!` invocation.
+    pub fn push_cfg_if(&mut self, item: CfgIf) -> &mut Self {
+        self.items.push(Item::CfgIf(item));
+        self
+    }
+
+    /// Push a new trait alias, returning a mutable reference to it.
+    ///
+    /// Trait aliases are unstable and require the generated crate to enable
+    /// `#![feature(trait_alias)]`.
+    #[cfg(feature = "nightly")]
+    pub fn new_trait_alias<I, T>(&mut self, name: impl ToString, bounds: I) -> &mut TraitAlias
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<crate::r#type::Type>,
+    {
+        self.push_trait_alias(TraitAlias::new(name, bounds));
+
+        match *self.items.last_mut().unwrap() {
+            Item::TraitAlias(ref mut v) => v,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Push a trait alias.
+    #[cfg(feature = "nightly")]
+    pub fn push_trait_alias(&mut self, item: TraitAlias) -> &mut Self {
+        self.items.push(Item::TraitAlias(item));
+        self
+    }
+
+    /// Push an unnamed const block, `const _: () = { ... };`, to the scope.
+    ///
+    /// This is commonly used for compile time assertions. Each line of `body`
+    /// is emitted inside the block.
+    pub fn const_block(&mut self, body: impl ToString) -> &mut Self {
+        let mut block = Block::new("const _: () =");
+        block.after(";");
+
+        for line in body.to_string().lines() {
+            block.line(line);
+        }
+
+        self.items.push(Item::ConstBlock(block));
+        self
+    }
+
     /// Push a raw string to the scope.
     ///
     /// This string will be included verbatim in the formatted string.
@@ -252,6 +803,20 @@ impl Scope {
         self
     }
 
+    /// Push a block of code to the scope.
+    ///
+    /// Unlike [`raw`](#method.raw), the code is re-indented to match the
+    /// indentation level of the scope. See [`CodeBlock`] for details.
+    pub fn code_block(&mut self, code: impl ToString) -> &mut Self {
+        self.push_code_block(CodeBlock::new(code))
+    }
+
+    /// Push a code block.
+    pub fn push_code_block(&mut self, item: CodeBlock) -> &mut Self {
+        self.items.push(Item::CodeBlock(item));
+        self
+    }
+
     /// Push a new `TypeAlias`, returning a mutable reference to it.
     pub fn new_type_alias(&mut self, name: impl ToString, target: impl ToString) -> &mut TypeAlias {
         self.push_type_alias(TypeAlias::new(name, target));
@@ -268,11 +833,149 @@ impl Scope {
         self
     }
 
+    /// Returns a copy of the scope with all raw string items removed.
+    pub fn clone_without_raw_items(&self) -> Scope {
+        Scope {
+            docs: self.docs.clone(),
+            features: self.features.clone(),
+            inner_attributes: self.inner_attributes.clone(),
+            imports: self.imports.clone(),
+            items: self
+                .items
+                .iter()
+                .filter(|item| !matches!(item, Item::Raw(_)))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Returns a new scope containing only the imports of this scope.
+    pub fn clone_only_imports(&self) -> Scope {
+        Scope {
+            docs: None,
+            features: vec![],
+            inner_attributes: vec![],
+            imports: self.imports.clone(),
+            items: vec![],
+        }
+    }
+
+    /// Returns a copy of the imports of the scope, keyed by path and then by
+    /// imported name.
+    pub fn clone_imports(&self) -> IndexMap<String, IndexMap<String, Import>> {
+        self.imports.clone()
+    }
+
+    /// Add the imports of another scope to this scope.
+    ///
+    /// Imports already present in this scope are left unchanged.
+    pub fn merge_imports(&mut self, other: &Scope) -> &mut Self {
+        for (path, imports) in &other.imports {
+            let entry = self.imports.entry(path.clone()).or_default();
+
+            for (ty, import) in imports {
+                entry.entry(ty.clone()).or_insert_with(|| import.clone());
+            }
+        }
+
+        self
+    }
+
+    /// Wrap the scope in a new module with the given name.
+    pub fn into_module(self, name: impl ToString) -> Module {
+        let mut module = Module::new(name);
+        *module.scope() = self;
+        module
+    }
+
+    /// Returns `true` if the scope has neither imports nor items.
+    pub fn is_empty(&self) -> bool {
+        !self.has_imports() && !self.has_items()
+    }
+
+    /// Returns `true` if the scope has any imports.
+    pub fn has_imports(&self) -> bool {
+        !self.imports.is_empty()
+    }
+
+    /// Returns `true` if the scope has any items.
+    pub fn has_items(&self) -> bool {
+        !self.items.is_empty()
+    }
+
+    /// Returns the number of items of each kind defined in this scope.
+    pub fn summary(&self) -> ScopeSummary {
+        let mut summary = ScopeSummary::default();
+
+        for item in &self.items {
+            match *item {
+                Item::Module(_) => summary.modules += 1,
+                Item::Struct(_) => summary.structs += 1,
+                Item::Function(_) => summary.fns += 1,
+                Item::Trait(_) => summary.traits += 1,
+                Item::Enum(_) => summary.enums += 1,
+                Item::Impl(_) => summary.impls += 1,
+                Item::Raw(_) => summary.raw_items += 1,
+                Item::CodeBlock(_) => summary.code_blocks += 1,
+                Item::TypeAlias(_) => summary.type_aliases += 1,
+                Item::Const(_) | Item::ConstBlock(_) => summary.consts += 1,
+                Item::CfgIf(_) => summary.cfg_ifs += 1,
+                #[cfg(feature = "nightly")]
+                Item::TraitAlias(_) => summary.traits += 1,
+            }
+        }
+
+        summary
+    }
+
     /// Return a string representation of the scope.
+    ///
+    /// This uses the default [`FormatterConfig`]. Use
+    /// [`fmt_with_config`](#method.fmt_with_config) to customize formatting.
+    #[deprecated(note = "use `fmt_with_config`")]
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
+        self.fmt_with_config(&FormatterConfig::default())
+    }
+
+    /// Return a string representation of a single item, as it would be
+    /// formatted in a scope.
+    ///
+    /// This uses the default [`FormatterConfig`].
+    pub fn render_item(item: &Item) -> String {
+        let mut ret = String::new();
+
+        item.fmt(&mut Formatter::new(&mut ret)).unwrap();
+
+        // Remove the trailing newline
+        if ret.as_bytes().last() == Some(&b'\n') {
+            ret.pop();
+        }
+
+        ret
+    }
+
+    /// Return the UTF-8 encoded representation of the scope.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.fmt_with_config(&FormatterConfig::default())
+            .into_bytes()
+    }
+
+    /// Return the UTF-8 encoded representation of the scope, prefixed with
+    /// a byte order mark.
+    pub fn to_bytes_bom(&self) -> Vec<u8> {
+        let mut ret = vec![0xEF, 0xBB, 0xBF];
+        ret.extend(self.to_bytes());
+        ret
+    }
+
+    /// Return a string representation of the scope, formatted using the
+    /// given options.
+    pub fn fmt_with_config(&self, config: &FormatterConfig) -> String {
         let mut ret = String::new();
 
-        self.fmt(&mut Formatter::new(&mut ret)).unwrap();
+        self.fmt(&mut Formatter::with_config(&mut ret, config))
+            .unwrap();
 
         // Remove the trailing newline
         if ret.as_bytes().last() == Some(&b'\n') {
@@ -282,8 +985,92 @@ impl Scope {
         ret
     }
 
+    /// Write the scope to `dir/name.rs`, returning the path of the file.
+    ///
+    /// This is intended for use in build scripts, e.g., with
+    /// `std::env::var("OUT_DIR")` as the directory. When `config.rustfmt` is
+    /// set, `rustfmt` is run on the written file and must be installed.
+    pub fn write_to_dir(
+        &self,
+        dir: impl AsRef<Path>,
+        name: &str,
+        config: &WriteConfig,
+    ) -> io::Result<PathBuf> {
+        let name = name.strip_suffix(".rs").unwrap_or(name);
+        let path = dir.as_ref().join(format!("{}.rs", name));
+
+        let mut code = self.fmt_with_config(&FormatterConfig {
+            edition: config.rust_edition,
+            ..Default::default()
+        });
+        code.push('\n');
+
+        std::fs::write(&path, code)?;
+
+        if config.rustfmt {
+            let status = Command::new("rustfmt")
+                .arg("--edition")
+                .arg(config.rust_edition.as_str())
+                .arg(&path)
+                .status()?;
+
+            if !status.success() {
+                return Err(io::Error::other(format!(
+                    "rustfmt failed on {}: {}",
+                    path.display(),
+                    status
+                )));
+            }
+        }
+
+        Ok(path)
+    }
+
     /// Formats the scope using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        let mut features = vec![];
+        self.collect_features(&mut features);
+
+        self.fmt_with_features(&features, fmt)
+    }
+
+    /// Formats the scope of a module, whose features are emitted by the
+    /// root scope instead.
+    pub(crate) fn fmt_nested(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        self.fmt_with_features(&[], fmt)
+    }
+
+    /// Collect the features of the scope and of all nested modules.
+    fn collect_features<'a>(&'a self, features: &mut Vec<&'a str>) {
+        for feature in &self.features {
+            if !features.contains(&feature.as_str()) {
+                features.push(feature);
+            }
+        }
+
+        for item in &self.items {
+            if let Item::Module(ref module) = *item {
+                module.scope.collect_features(features);
+            }
+        }
+    }
+
+    fn fmt_with_features(&self, features: &[&str], fmt: &mut Formatter<'_>) -> fmt::Result {
+        // Inner attributes must come before anything else in the scope
+        if !features.is_empty() {
+            writeln!(fmt, "#![feature({})]", features.join(", "))?;
+        }
+
+        for attr in &self.inner_attributes {
+            writeln!(fmt, "#![{}]", attr)?;
+        }
+
+        if (!features.is_empty() || !self.inner_attributes.is_empty())
+            && (self.docs.is_some() || !self.imports.is_empty() || !self.items.is_empty())
+        {
+            writeln!(fmt)?;
+        }
+
         if let Some(ref docs) = self.docs {
             docs.fmt(fmt)?;
         }
@@ -299,58 +1086,69 @@ impl Scope {
                 writeln!(fmt)?;
             }
 
-            match *item {
-                Item::Module(ref v) => v.fmt(fmt)?,
-                Item::Struct(ref v) => v.fmt(fmt)?,
-                Item::Function(ref v) => v.fmt(false, fmt)?,
-                Item::Trait(ref v) => v.fmt(fmt)?,
-                Item::Enum(ref v) => v.fmt(fmt)?,
-                Item::Impl(ref v) => v.fmt(fmt)?,
-                Item::Raw(ref v) => {
-                    writeln!(fmt, "{}", v)?;
-                }
-                Item::TypeAlias(ref v) => v.fmt(fmt)?,
-                Item::Const(ref v) => v.fmt(fmt)?,
-            }
+            item.fmt(fmt)?;
         }
 
         Ok(())
     }
 
     fn fmt_imports(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        // First, collect all visibilities
-        let mut visibilities = vec![];
+        // First, collect all visibility and `cfg` combinations
+        let mut groups = vec![];
 
         for (_, imports) in &self.imports {
             for (_, import) in imports {
-                if !visibilities.contains(&import.vis) {
-                    visibilities.push(import.vis.clone());
+                let group = (&import.vis, &import.cfg);
+
+                if !groups.contains(&group) {
+                    groups.push(group);
                 }
             }
         }
 
         let mut tys = vec![];
 
-        // Loop over all visibilities and format the associated imports
-        for vis in &visibilities {
+        // Loop over all groups and format the associated imports
+        for &(vis, cfg) in &groups {
             for (path, imports) in &self.imports {
                 tys.clear();
+                let mut glob = false;
 
                 for (ty, import) in imports {
-                    if *vis == import.vis {
+                    if import.vis != *vis || import.cfg != *cfg {
+                        continue;
+                    }
+
+                    if ty == "*" {
+                        glob = true;
+                        continue;
+                    }
+
+                    // an alias equal to the imported name is redundant
+                    let alias = import.alias.as_ref().filter(|alias| *alias != ty);
+
+                    let ty = match alias {
+                        Some(alias) => format!("{} as {}", ty, alias),
+                        None => ty.to_string(),
+                    };
+
+                    // `self` always comes first in a group
+                    if is_self(&ty) {
+                        tys.insert(0, ty);
+                    } else {
                         tys.push(ty);
                     }
                 }
 
                 if !tys.is_empty() {
-                    if let Some(ref vis) = *vis {
-                        write!(fmt, "{} ", vis)?;
-                    }
-
-                    write!(fmt, "use {}::", path)?;
+                    fmt_use_prefix(vis, cfg, fmt)?;
+                    write!(fmt, "use {}", path)?;
 
-                    if tys.len() > 1 {
-                        write!(fmt, "{{")?;
+                    if tys.len() == 1 && is_self(&tys[0]) {
+                        // `use path::self;` is not valid
+                        writeln!(fmt, "{};", &tys[0]["self".len()..])?;
+                    } else if tys.len() > 1 {
+                        write!(fmt, "::{{")?;
 
                         for (i, ty) in tys.iter().enumerate() {
                             if i != 0 {
@@ -361,12 +1159,48 @@ impl Scope {
 
                         writeln!(fmt, "}};")?;
                     } else if tys.len() == 1 {
-                        writeln!(fmt, "{};", tys[0])?;
+                        writeln!(fmt, "::{};", tys[0])?;
                     }
                 }
+
+                // a glob import always follows the named imports of its path
+                if glob {
+                    fmt_use_prefix(vis, cfg, fmt)?;
+                    writeln!(fmt, "use {}::*;", path)?;
+                }
             }
         }
 
         Ok(())
     }
 }
+
+/// Write the `#[cfg(...)]` attribute and visibility preceding a `use`.
+fn fmt_use_prefix(
+    vis: &Option<String>,
+    cfg: &Option<String>,
+    fmt: &mut Formatter<'_>,
+) -> fmt::Result {
+    if let Some(ref cfg) = *cfg {
+        writeln!(fmt, "#[cfg({})]", cfg)?;
+    }
+
+    if let Some(ref vis) = *vis {
+        write!(fmt, "{} ", vis)?;
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the formatted import refers to the path itself.
+fn is_self(ty: &str) -> bool {
+    ty == "self" || ty.starts_with("self ")
+}
+
+/// Compares an item name against a name given to a lookup method.
+fn name_eq<Q: ?Sized>(item_name: &str, name: &Q) -> bool
+where
+    String: PartialEq<Q>,
+{
+    item_name.to_string() == *name
+}
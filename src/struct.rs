@@ -2,7 +2,8 @@ use std::fmt::{self, Write};
 
 use crate::field::Field;
 use crate::fields::Fields;
-use crate::formatter::Formatter;
+use crate::formatter::{fmt_bounds, Formatter};
+use crate::r#impl::Impl;
 use crate::type_def::TypeDef;
 
 use crate::r#type::Type;
@@ -14,6 +15,12 @@ pub struct Struct {
 
     /// Struct fields
     fields: Fields,
+
+    /// Visibility applied to fields added to the struct
+    fields_vis: Option<String>,
+
+    /// Whether a struct without fields is emitted as a unit struct
+    unit: bool,
 }
 
 impl Struct {
@@ -22,6 +29,8 @@ impl Struct {
         Struct {
             type_def: TypeDef::new(name),
             fields: Fields::Empty,
+            fields_vis: None,
+            unit: true,
         }
     }
 
@@ -30,6 +39,31 @@ impl Struct {
         &self.type_def.ty
     }
 
+    /// Rename the struct.
+    pub fn rename(&mut self, new_name: impl ToString) -> &mut Self {
+        self.type_def.ty.set_name(new_name);
+        self
+    }
+
+    /// Returns the number of fields of the struct, named or tuple.
+    pub fn field_count(&self) -> usize {
+        match self.fields {
+            Fields::Named(ref fields) => fields.len(),
+            Fields::Tuple(ref tys) => tys.len(),
+            Fields::Empty => 0,
+        }
+    }
+
+    /// Set whether a struct without fields is emitted as a unit struct,
+    /// `struct Foo;`, or with empty braces, `struct Foo {}`.
+    ///
+    /// Structs without fields are emitted as unit structs by default. This
+    /// has no effect once fields are added.
+    pub fn set_unit(&mut self, unit: bool) -> &mut Self {
+        self.unit = unit;
+        self
+    }
+
     /// Set the structure visibility.
     pub fn vis(&mut self, vis: impl ToString) -> &mut Self {
         self.type_def.vis(vis);
@@ -57,18 +91,97 @@ impl Struct {
         self
     }
 
+    /// Returns the structure documentation, if any.
+    pub fn documentation(&self) -> Option<&str> {
+        self.type_def.documentation()
+    }
+
     /// Add a new type that the struct should derive.
     pub fn derive(&mut self, name: impl ToString) -> &mut Self {
         self.type_def.derive(name);
         self
     }
 
+    /// Derive `Debug` for the struct and return an `impl std::fmt::Display`
+    /// block delegating to it.
+    ///
+    /// Each type parameter of the struct is bound by `std::fmt::Debug`. The
+    /// returned impl should be pushed into the same scope as the struct.
+    ///
+    /// ```
+    /// use codegen::{Scope, Struct};
+    ///
+    /// let mut scope = Scope::new();
+    ///
+    /// let mut foo = Struct::new("Foo");
+    /// let display = foo.with_derive_debug_display();
+    ///
+    /// scope.push_struct(foo);
+    /// scope.push_impl(display);
+    /// ```
+    pub fn with_derive_debug_display(&mut self) -> Impl {
+        self.derive("Debug");
+
+        let mut ret = self.type_def.new_impl();
+        ret.impl_trait("std::fmt::Display");
+
+        for param in self.type_def.type_params() {
+            ret.bound(param, "std::fmt::Debug");
+        }
+
+        ret.new_fn("fmt")
+            .arg_ref_self()
+            .arg("f", "&mut std::fmt::Formatter<'_>")
+            .ret("std::fmt::Result")
+            .line("write!(f, \"{:?}\", self)");
+
+        ret
+    }
+
+    /// Add a `#[derive_where(...)]` attribute to the struct.
+    ///
+    /// This is the conditional derive provided by the `derive_where` crate and
+    /// is emitted separately from the standard `#[derive(...)]` attribute. An
+    /// empty `condition` emits the traits without a `;` separated bound.
+    ///
+    /// ```
+    /// use codegen::Struct;
+    ///
+    /// let mut foo = Struct::new("Foo");
+    ///
+    /// // emits `#[derive_where(Clone, Debug; T: Clone)]`
+    /// foo.derive_where(&["Clone", "Debug"], "T: Clone");
+    /// ```
+    pub fn derive_where(&mut self, traits: &[&str], condition: &str) -> &mut Self {
+        let traits = traits.join(", ");
+
+        if condition.is_empty() {
+            self.type_def.attr(format!("derive_where({})", traits));
+        } else {
+            self.type_def
+                .attr(format!("derive_where({}; {})", traits, condition));
+        }
+
+        self
+    }
+
     /// Add a new attribute to the struct
     pub fn attr(&mut self, attr: impl ToString) -> &mut Self {
         self.type_def.attr(attr);
         self
     }
 
+    /// Insert an attribute at the given position among the struct's
+    /// attributes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of attributes.
+    pub fn attribute_at(&mut self, index: usize, attr: impl ToString) -> &mut Self {
+        self.type_def.attribute_at(index, attr);
+        self
+    }
+
     /// Specify lint attribute to supress a warning or error.
     pub fn allow(&mut self, allow: impl ToString) -> &mut Self {
         self.type_def.allow(allow);
@@ -81,6 +194,15 @@ impl Struct {
         self
     }
 
+    /// Specify the alignment of the struct, i.e., `#[repr(align(n))]`.
+    ///
+    /// The alignment is combined with a representation set beforehand with
+    /// [`repr`](#method.repr), e.g., `#[repr(C, align(8))]`.
+    pub fn repr_align(&mut self, align: usize) -> &mut Self {
+        self.type_def.repr_align(align);
+        self
+    }
+
     /// Add an arbitrary macro.
     pub fn r#macro(&mut self, r#macro: impl ToString) -> &mut Self {
         self.type_def.r#macro(r#macro);
@@ -91,11 +213,29 @@ impl Struct {
     ///
     /// A struct can either set named fields with this function or tuple fields
     /// with `push_tuple_field`, but not both.
-    pub fn push_field(&mut self, field: Field) -> &mut Self {
+    pub fn push_field(&mut self, mut field: Field) -> &mut Self {
+        if field.visibility.is_none() {
+            field.visibility = self.fields_vis.clone();
+        }
+
         self.fields.push_named(field);
         self
     }
 
+    /// Push a group of named fields to the struct, preceded by a
+    /// `// comment` line.
+    pub fn add_field_group(&mut self, comment: impl ToString, fields: Vec<Field>) -> &mut Self {
+        for (i, mut field) in fields.into_iter().enumerate() {
+            if i == 0 {
+                field.group_comment = Some(comment.to_string());
+            }
+
+            self.push_field(field);
+        }
+
+        self
+    }
+
     /// Add a named field to the struct.
     ///
     /// A struct can either set named fields with this function or tuple fields
@@ -104,7 +244,7 @@ impl Struct {
     where
         T: Into<Type>,
     {
-        self.fields.named(name, ty);
+        self.new_field(name, ty);
         self
     }
 
@@ -116,7 +256,134 @@ impl Struct {
     where
         T: Into<Type>,
     {
-        self.fields.new_named(name, ty)
+        let vis = self.fields_vis.clone();
+
+        let field = self.fields.new_named(name, ty);
+        field.visibility = vis;
+        field
+    }
+
+    /// Returns an iterator over the named fields of the struct.
+    ///
+    /// Tuple fields are not visited.
+    pub fn fields(&self) -> impl Iterator<Item = &Field> {
+        let fields = match self.fields {
+            Fields::Named(ref fields) => &fields[..],
+            _ => &[],
+        };
+
+        fields.iter()
+    }
+
+    /// Returns a mutable iterator over the named fields of the struct.
+    ///
+    /// Tuple fields are not visited.
+    pub fn fields_mut(&mut self) -> impl Iterator<Item = &mut Field> {
+        let fields = match self.fields {
+            Fields::Named(ref mut fields) => &mut fields[..],
+            _ => &mut [],
+        };
+
+        fields.iter_mut()
+    }
+
+    /// Returns a reference to the named field with the given name.
+    pub fn get_field(&self, name: &str) -> Option<&Field> {
+        match self.fields {
+            Fields::Named(ref fields) => fields.iter().find(|f| f.name == name),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the named field with the given name.
+    pub fn get_field_mut(&mut self, name: &str) -> Option<&mut Field> {
+        match self.fields {
+            Fields::Named(ref mut fields) => fields.iter_mut().find(|f| f.name == name),
+            _ => None,
+        }
+    }
+
+    /// Returns the type of the tuple field at the given index.
+    pub fn get_field_at(&self, index: usize) -> Option<&Type> {
+        match self.fields {
+            Fields::Tuple(ref tys) => tys.get(index).map(|(_, ty)| ty),
+            _ => None,
+        }
+    }
+
+    /// Remove the named field with the given name, returning it.
+    ///
+    /// The order of the remaining fields is preserved.
+    pub fn remove_field(&mut self, name: &str) -> Option<Field> {
+        let field = match self.fields {
+            Fields::Named(ref mut fields) => {
+                let index = fields.iter().position(|f| f.name == name)?;
+                fields.remove(index)
+            }
+            _ => return None,
+        };
+
+        if self.field_count() == 0 {
+            self.fields = Fields::Empty;
+        }
+
+        Some(field)
+    }
+
+    /// Reorder the named fields of the struct.
+    ///
+    /// Fields named in `order` are moved to the front, in the given order.
+    /// The remaining fields follow in their current order. The struct is
+    /// left unchanged if an error is returned.
+    ///
+    /// ```
+    /// use codegen::Struct;
+    ///
+    /// let mut foo = Struct::new("Foo");
+    /// foo.field("a", "u8").field("b", "u8").field("c", "u8");
+    ///
+    /// // fields are now ordered `c`, `a`, `b`
+    /// foo.reorder_fields(&["c"]).unwrap();
+    /// ```
+    pub fn reorder_fields(&mut self, order: &[&str]) -> Result<&mut Self, ReorderError> {
+        for (i, name) in order.iter().enumerate() {
+            if order[..i].contains(name) {
+                return Err(ReorderError::DuplicateField(name.to_string()));
+            }
+
+            if self.get_field(name).is_none() {
+                return Err(ReorderError::UnknownField(name.to_string()));
+            }
+        }
+
+        if let Fields::Named(ref mut fields) = self.fields {
+            fields.sort_by_key(|f| {
+                order
+                    .iter()
+                    .position(|name| f.name == *name)
+                    .unwrap_or(order.len())
+            });
+        }
+
+        Ok(self)
+    }
+
+    /// Set the visibility of all named fields of the struct.
+    ///
+    /// The visibility is applied to every field currently in the struct, as
+    /// well as to fields added afterwards that do not set their own
+    /// visibility.
+    pub fn visibility_fields(&mut self, vis: impl ToString) -> &mut Self {
+        let vis = vis.to_string();
+
+        if let Fields::Named(ref mut fields) = self.fields {
+            for field in fields {
+                field.visibility = Some(vis.clone());
+            }
+        }
+
+        self.fields_vis = Some(vis);
+        self
     }
 
     /// Add a tuple field to the struct.
@@ -131,21 +398,193 @@ impl Struct {
         self
     }
 
+    /// Add a tuple field with the given visibility to the struct, e.g.,
+    /// `pub(crate) u32`.
+    ///
+    /// A struct can either set tuple fields with this function or named fields
+    /// with `field`, but not both.
+    pub fn tuple_field_with_vis<T>(&mut self, vis: impl ToString, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        self.fields.tuple_with_vis(vis, ty);
+        self
+    }
+
+    /// Return an `impl std::hash::Hash` block hashing the fields of the struct.
+    ///
+    /// Fields are hashed in order. Named fields can be excluded with
+    /// [`Field::skip_hash`]. As with `#[derive(Hash)]`, each type parameter
+    /// of the struct is bound by `std::hash::Hash`.
+    pub fn generate_hash_impl(&self) -> Impl {
+        let mut ret = self.type_def.new_impl();
+        ret.impl_trait("std::hash::Hash");
+
+        for param in self.type_def.type_params() {
+            ret.bound(param, "std::hash::Hash");
+        }
+
+        let func = ret
+            .new_fn("hash")
+            .generic("H: std::hash::Hasher")
+            .arg_ref_self()
+            .arg("state", "&mut H");
+
+        match self.fields {
+            Fields::Named(ref fields) => {
+                for field in fields.iter().filter(|field| !field.skip_hash) {
+                    func.line(format!("self.{}.hash(state);", field.name));
+                }
+            }
+            Fields::Tuple(ref tys) => {
+                for i in 0..tys.len() {
+                    func.line(format!("self.{}.hash(state);", i));
+                }
+            }
+            Fields::Empty => {}
+        }
+
+        ret
+    }
+
+    /// Return an `impl PartialEq` block comparing the fields of the struct.
+    ///
+    /// Fields named in `skip_fields` do not participate in the comparison.
+    /// Tuple fields are named by their index, e.g., `"0"`. As with
+    /// `#[derive(PartialEq)]`, each type parameter of the struct is bound by
+    /// `PartialEq`.
+    pub fn generate_partial_eq_impl(&self, skip_fields: &[&str]) -> Impl {
+        let names: Vec<String> = match self.fields {
+            Fields::Named(ref fields) => fields.iter().map(|f| f.name.clone()).collect(),
+            Fields::Tuple(ref tys) => (0..tys.len()).map(|i| i.to_string()).collect(),
+            Fields::Empty => vec![],
+        };
+
+        let comparisons: Vec<String> = names
+            .iter()
+            .filter(|name| !skip_fields.contains(&name.as_str()))
+            .map(|name| format!("self.{name} == other.{name}", name = name))
+            .collect();
+
+        let mut ret = self.type_def.new_impl();
+        ret.impl_trait("PartialEq");
+
+        for param in self.type_def.type_params() {
+            ret.bound(param, "PartialEq");
+        }
+
+        ret.new_fn("eq")
+            .arg_ref_self()
+            .arg("other", "&Self")
+            .ret("bool")
+            .line(if comparisons.is_empty() {
+                "true".to_string()
+            } else {
+                comparisons.join(" && ")
+            });
+
+        ret
+    }
+
+    /// Return a JSON schema describing the struct.
+    ///
+    /// Named fields become object properties, with field documentation used
+    /// as the property `"description"`. Fields are required unless their type
+    /// is an `Option`. Types without a JSON equivalent are referenced as
+    /// `"#/$defs/Name"`.
+    #[cfg(feature = "json-schema")]
+    pub fn to_json_schema(&self) -> serde_json::Value {
+        use serde_json::{json, Map};
+
+        use crate::json_schema::{is_optional, type_schema};
+
+        let title = self.ty().name();
+
+        match self.fields {
+            Fields::Named(ref fields) => {
+                let mut properties = Map::new();
+                let mut required = vec![];
+
+                for field in fields {
+                    let mut schema = type_schema(&field.ty);
+
+                    if !field.documentation.is_empty() {
+                        schema["description"] = json!(field.documentation);
+                    }
+
+                    if !is_optional(&field.ty) {
+                        required.push(json!(field.name));
+                    }
+
+                    properties.insert(field.name.clone(), schema);
+                }
+
+                json!({
+                    "title": title,
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                })
+            }
+            Fields::Tuple(ref tys) => json!({
+                "title": title,
+                "type": "array",
+                "prefixItems": tys.iter().map(|(_, ty)| type_schema(ty)).collect::<Vec<_>>(),
+                "minItems": tys.len(),
+                "maxItems": tys.len(),
+            }),
+            Fields::Empty => json!({
+                "title": title,
+                "type": "null",
+            }),
+        }
+    }
+
     /// Formats the struct using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        self.type_def.fmt_head("struct", &[], fmt)?;
-        self.fields.fmt(fmt)?;
+        let bounds = self.type_def.fmt_signature("struct", &[], fmt)?;
 
         match self.fields {
-            Fields::Empty => {
+            Fields::Tuple(..) => {
+                // The `where` clause of a tuple struct follows its fields
+                self.fields.fmt(fmt)?;
+                fmt_bounds(&bounds, fmt)?;
                 writeln!(fmt, ";")?;
             }
-            Fields::Tuple(..) => {
+            Fields::Empty if !self.unit => {
+                fmt_bounds(&bounds, fmt)?;
+                fmt.block(|_| Ok(()))?;
+            }
+            Fields::Empty => {
+                fmt_bounds(&bounds, fmt)?;
                 writeln!(fmt, ";")?;
             }
-            _ => {}
+            Fields::Named(..) => {
+                fmt_bounds(&bounds, fmt)?;
+                self.fields.fmt(fmt)?;
+            }
         }
 
         Ok(())
     }
 }
+
+/// Error returned by [`Struct::reorder_fields`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReorderError {
+    /// The struct has no named field with the given name
+    UnknownField(String),
+    /// The field name is given more than once
+    DuplicateField(String),
+}
+
+impl fmt::Display for ReorderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            ReorderError::UnknownField(ref name) => write!(f, "unknown field `{}`", name),
+            ReorderError::DuplicateField(ref name) => write!(f, "duplicate field `{}`", name),
+        }
+    }
+}
+
+impl std::error::Error for ReorderError {}
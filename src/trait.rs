@@ -5,6 +5,8 @@ use crate::associated_type::AssociatedType;
 use crate::bound::Bound;
 use crate::formatter::{fmt_bound_rhs, Formatter};
 use crate::function::Function;
+use crate::module::Module;
+use crate::r#impl::Impl;
 use crate::type_def::TypeDef;
 
 use crate::r#type::Type;
@@ -18,6 +20,7 @@ pub struct Trait {
     attributes: Vec<String>,
     associated_tys: Vec<AssociatedType>,
     fns: Vec<Function>,
+    object_unsafe: bool,
 }
 
 impl Trait {
@@ -30,6 +33,7 @@ impl Trait {
             attributes: Vec::new(),
             associated_tys: Vec::new(),
             fns: Vec::new(),
+            object_unsafe: false,
         }
     }
 
@@ -38,6 +42,12 @@ impl Trait {
         &self.type_def.ty
     }
 
+    /// Rename the trait.
+    pub fn rename(&mut self, new_name: impl ToString) -> &mut Self {
+        self.type_def.ty.set_name(new_name);
+        self
+    }
+
     /// Set the trait visibility.
     pub fn vis(&mut self, vis: impl ToString) -> &mut Self {
         self.type_def.vis(vis);
@@ -50,6 +60,26 @@ impl Trait {
         self
     }
 
+    /// Add a `#[doc(notable_trait)]` attribute, highlighting the trait in
+    /// rustdoc.
+    ///
+    /// The attribute is unstable and requires the generated crate to enable
+    /// `#![feature(doc_notable_trait)]`.
+    #[cfg(feature = "nightly")]
+    pub fn notable_trait(&mut self) -> &mut Self {
+        self.attributes.insert(0, "doc(notable_trait)".to_string());
+        self
+    }
+
+    /// Mark the trait as intentionally not object safe.
+    ///
+    /// This emits a `// Not object safe` comment above the trait so that
+    /// readers know the trait is not meant to be used as `dyn Trait`.
+    pub fn object_unsafe(&mut self, object_unsafe: bool) -> &mut Self {
+        self.object_unsafe = object_unsafe;
+        self
+    }
+
     /// Add a generic to the trait
     pub fn generic(&mut self, name: impl ToString) -> &mut Self {
         self.type_def.ty.generic(name);
@@ -80,12 +110,80 @@ impl Trait {
         self
     }
 
+    /// Apply the sealed trait pattern to this trait.
+    ///
+    /// Returns the trait with `private::Sealed` added as a parent, along with
+    /// the `private` module defining the `Sealed` trait. Both should be pushed
+    /// into the same scope, and every type implementing the trait must also
+    /// implement `private::Sealed`.
+    ///
+    /// ```
+    /// use codegen::{Scope, Trait};
+    ///
+    /// let mut scope = Scope::new();
+    ///
+    /// let (tr, private) = Trait::new("Foo").sealed();
+    /// scope.push_trait(tr);
+    /// scope.push_module(private);
+    /// ```
+    pub fn sealed(mut self) -> (Trait, Module) {
+        self.parent("private::Sealed");
+
+        let mut private = Module::new("private");
+        private.new_trait("Sealed").vis("pub");
+
+        (self, private)
+    }
+
+    /// Return a blanket impl of the trait for all types satisfying
+    /// `constraint`, i.e., `impl<T: Constraint> Trait for T {}`.
+    ///
+    /// The generics of the trait are declared on the impl block. The
+    /// implementing type is named `T`, or `T0`, `T1`, etc. if the trait
+    /// already has a generic named `T`.
+    ///
+    /// ```
+    /// use codegen::Trait;
+    ///
+    /// let tr = Trait::new("DisplayExt");
+    ///
+    /// // impl<T: std::fmt::Display> DisplayExt for T
+    /// let imp = tr.generate_blanket_impl("std::fmt::Display");
+    /// ```
+    pub fn generate_blanket_impl<T>(&self, constraint: T) -> Impl
+    where
+        T: Into<Type>,
+    {
+        let mut bound = String::new();
+        constraint
+            .into()
+            .fmt(&mut Formatter::new(&mut bound))
+            .unwrap();
+
+        let params = self.type_def.type_params();
+        let name = std::iter::once("T".to_string())
+            .chain((0..).map(|i| format!("T{}", i)))
+            .find(|name| !params.contains(name))
+            .unwrap();
+
+        let mut ret = Impl::new(&name);
+        self.type_def.declare_generics(&mut ret);
+        ret.generic(format!("{}: {}", name, bound))
+            .impl_trait(self.type_def.generic_args());
+        ret
+    }
+
     /// Set the trait documentation.
     pub fn doc(&mut self, docs: impl ToString) -> &mut Self {
         self.type_def.doc(docs);
         self
     }
 
+    /// Returns the trait documentation, if any.
+    pub fn documentation(&self) -> Option<&str> {
+        self.type_def.documentation()
+    }
+
     /// Add an associated const. Returns a mutable reference to the new
     /// associated const for futher configuration.
     pub fn associated_const<T>(&mut self, name: impl ToString, ty: T) -> &mut AssociatedConst
@@ -103,10 +201,13 @@ impl Trait {
     /// Add an associated type. Returns a mutable reference to the new
     /// associated type for futher configuration.
     pub fn associated_type(&mut self, name: impl ToString) -> &mut AssociatedType {
-        self.associated_tys.push(AssociatedType(Bound {
-            name: name.to_string(),
-            bound: vec![],
-        }));
+        self.associated_tys.push(AssociatedType(
+            Bound {
+                name: name.to_string(),
+                bound: vec![],
+            },
+            vec![],
+        ));
 
         self.associated_tys.last_mut().unwrap()
     }
@@ -128,10 +229,12 @@ impl Trait {
 
     /// Formats the scope using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        for attr in &self.attributes {
-            writeln!(fmt, "#[{}]", attr)?;
+        if self.object_unsafe {
+            writeln!(fmt, "// Not object safe")?;
         }
 
+        fmt.emit_attributes(&self.attributes)?;
+
         self.type_def.fmt_head("trait", &self.parents, fmt)?;
 
         fmt.block(|fmt| {
@@ -157,6 +260,7 @@ impl Trait {
             // format associated types
             if !assoc_tys.is_empty() {
                 for ty in assoc_tys {
+                    let where_bounds = &ty.1;
                     let ty = &ty.0;
 
                     write!(fmt, "type {}", ty.name)?;
@@ -166,6 +270,17 @@ impl Trait {
                         fmt_bound_rhs(&ty.bound, fmt)?;
                     }
 
+                    for (i, bound) in where_bounds.iter().enumerate() {
+                        if i == 0 {
+                            write!(fmt, " where ")?;
+                        } else {
+                            write!(fmt, ", ")?;
+                        }
+
+                        write!(fmt, "{}: ", bound.name)?;
+                        fmt_bound_rhs(&bound.bound, fmt)?;
+                    }
+
                     writeln!(fmt, ";")?;
                 }
             }
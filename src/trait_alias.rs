@@ -0,0 +1,72 @@
+use std::fmt::{self, Write};
+
+use crate::formatter::{fmt_bound_rhs, Formatter};
+use crate::type_def::TypeDef;
+
+use crate::r#type::Type;
+
+/// Defines a trait alias, e.g., `trait Alias = Trait1 + Trait2;`.
+///
+/// Trait aliases are unstable and require the crate to enable
+/// `#![feature(trait_alias)]`.
+#[derive(Debug, Clone)]
+pub struct TraitAlias {
+    type_def: TypeDef,
+    bounds: Vec<Type>,
+}
+
+impl TraitAlias {
+    /// Return a trait alias definition with the provided name and bounds.
+    pub fn new<I, T>(name: impl ToString, bounds: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Type>,
+    {
+        TraitAlias {
+            type_def: TypeDef::new(name),
+            bounds: bounds.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Returns a reference to the type
+    pub fn ty(&self) -> &Type {
+        &self.type_def.ty
+    }
+
+    /// Set the trait alias visibility.
+    pub fn vis(&mut self, vis: impl ToString) -> &mut Self {
+        self.type_def.vis(vis);
+        self
+    }
+
+    /// Add a generic to the trait alias.
+    pub fn generic(&mut self, name: impl ToString) -> &mut Self {
+        self.type_def.ty.generic(name);
+        self
+    }
+
+    /// Add a bound to the trait alias.
+    pub fn bound<T>(&mut self, ty: T) -> &mut Self
+    where
+        T: Into<Type>,
+    {
+        self.bounds.push(ty.into());
+        self
+    }
+
+    /// Set the trait alias documentation.
+    pub fn doc(&mut self, docs: impl ToString) -> &mut Self {
+        self.type_def.doc(docs);
+        self
+    }
+
+    /// Formats the trait alias using the given formatter.
+    pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        assert!(!self.bounds.is_empty(), "trait aliases must have bounds");
+
+        self.type_def.fmt_head("trait", &[], fmt)?;
+        write!(fmt, " = ")?;
+        fmt_bound_rhs(&self.bounds, fmt)?;
+        writeln!(fmt, ";")
+    }
+}
@@ -18,6 +18,32 @@ impl Type {
         }
     }
 
+    /// Return the never type, `!`.
+    pub fn never() -> Self {
+        Type::new("!")
+    }
+
+    /// Return the unit type, `()`.
+    pub fn unit() -> Self {
+        Type::new("()")
+    }
+
+    /// Returns the name of the type, without any generics added with
+    /// [`generic`](#method.generic).
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the name of the type, keeping its generics.
+    pub(crate) fn set_name(&mut self, name: impl ToString) {
+        self.name = name.to_string();
+    }
+
+    /// Returns the generics of the type.
+    pub(crate) fn generics(&self) -> &[Type] {
+        &self.generics
+    }
+
     /// Add a generic to the type.
     pub fn generic<T>(&mut self, ty: T) -> &mut Self
     where
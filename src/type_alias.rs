@@ -3,7 +3,9 @@ use std::fmt::Write;
 
 use crate::{type_def::TypeDef, Formatter, Type};
 
-/// https://rust-lang.github.io/chalk/book/types/rust_types/alias.html#alias-types
+/// Defines a type alias.
+///
+/// See <https://rust-lang.github.io/chalk/book/types/rust_types/alias.html#alias-types>
 #[derive(Debug, Clone)]
 pub struct TypeAlias {
     type_def: TypeDef,
@@ -11,12 +13,15 @@ pub struct TypeAlias {
 }
 
 impl TypeAlias {
+    /// Return a type alias with the provided name, aliasing the given type.
     pub fn new(name: impl ToString, ty: impl ToString) -> Self {
         Self {
             type_def: TypeDef::new(name),
             ty: Type::new(ty),
         }
     }
+
+    /// Formats the type alias using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
         self.type_def.fmt_head("type", &[], fmt)?;
         write!(fmt, " = ")?;
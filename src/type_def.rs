@@ -2,7 +2,8 @@ use std::fmt::{self, Write};
 
 use crate::bound::Bound;
 use crate::docs::Docs;
-use crate::formatter::{fmt_bounds, Formatter};
+use crate::formatter::{fmt_bounds, split_inline_bound, Formatter};
+use crate::r#impl::Impl;
 
 use crate::r#type::Type;
 
@@ -58,10 +59,18 @@ impl TypeDef {
         self.attributes.push(attr.to_string());
     }
 
+    pub fn attribute_at(&mut self, index: usize, attr: impl ToString) {
+        self.attributes.insert(index, attr.to_string());
+    }
+
     pub fn doc(&mut self, docs: impl ToString) {
         self.docs = Some(Docs::new(docs));
     }
 
+    pub fn documentation(&self) -> Option<&str> {
+        self.docs.as_ref().map(Docs::as_str)
+    }
+
     pub fn derive(&mut self, name: impl ToString) {
         self.derive.push(name.to_string());
     }
@@ -74,12 +83,88 @@ impl TypeDef {
         self.repr = Some(repr.to_string());
     }
 
+    pub fn repr_align(&mut self, align: usize) {
+        self.repr = Some(match self.repr.take() {
+            Some(repr) => format!("{}, align({})", repr, align),
+            None => format!("align({})", align),
+        });
+    }
+
+    /// Return a new impl block for the type, declaring the type's generics on
+    /// the impl block.
+    ///
+    /// Inline bounds of the generics are kept on the impl block's generics
+    /// but stripped from the target type, and the type's `where` bounds are
+    /// copied to the impl block.
+    pub fn new_impl(&self) -> Impl {
+        let mut ret = Impl::new(self.generic_args());
+        self.declare_generics(&mut ret);
+        ret
+    }
+
+    /// Returns the type with its generics as arguments, i.e., with any
+    /// inline bounds stripped, e.g., `Foo<T>` for `Foo<T: Clone>`.
+    pub fn generic_args(&self) -> Type {
+        let mut ret = Type::new(self.ty.name());
+
+        for generic in self.ty.generics() {
+            if !generic.generics().is_empty() {
+                ret.generic(generic);
+                continue;
+            }
+
+            for param in split_generics(generic.name()) {
+                ret.generic(generic_arg(param));
+            }
+        }
+
+        ret
+    }
+
+    /// Declare the type's generics and `where` bounds on an impl block.
+    pub fn declare_generics(&self, imp: &mut Impl) {
+        for generic in self.ty.generics() {
+            imp.generic(generic.name());
+        }
+
+        for bound in &self.bounds {
+            for ty in &bound.bound {
+                imp.bound(&bound.name, ty);
+            }
+        }
+    }
+
+    /// Returns the names of the type parameters of the type, excluding
+    /// lifetimes and const generics.
+    pub fn type_params(&self) -> Vec<String> {
+        self.ty
+            .generics()
+            .iter()
+            .filter(|generic| generic.generics().is_empty())
+            .flat_map(|generic| split_generics(generic.name()))
+            .filter(|param| !param.starts_with('\'') && !param.starts_with("const "))
+            .map(|param| generic_arg(param).to_string())
+            .collect()
+    }
+
     pub fn fmt_head(
         &self,
         keyword: &str,
         parents: &[Type],
         fmt: &mut Formatter<'_>,
     ) -> fmt::Result {
+        let bounds = self.fmt_signature(keyword, parents, fmt)?;
+        fmt_bounds(&bounds, fmt)
+    }
+
+    /// Formats the head up to, but excluding, the `where` clause, returning
+    /// the bounds to emit in it.
+    pub fn fmt_signature(
+        &self,
+        keyword: &str,
+        parents: &[Type],
+        fmt: &mut Formatter<'_>,
+    ) -> Result<Vec<Bound>, fmt::Error> {
         if let Some(ref docs) = self.docs {
             docs.fmt(fmt)?;
         }
@@ -95,7 +180,34 @@ impl TypeDef {
         }
 
         write!(fmt, "{} ", keyword)?;
-        self.ty.fmt(fmt)?;
+
+        let mut bounds = vec![];
+
+        if fmt.config().always_use_where_clause {
+            // Move inline bounds, e.g., `T: Clone`, to the `where` clause
+            let mut ty = Type::new(self.ty.name());
+
+            for generic in self.ty.generics() {
+                match split_inline_bound(generic.name()) {
+                    Some((name, bound)) if generic.generics().is_empty() => {
+                        bounds.push(Bound {
+                            name: name.to_string(),
+                            bound: vec![bound.into()],
+                        });
+                        ty.generic(name);
+                    }
+                    _ => {
+                        ty.generic(generic);
+                    }
+                }
+            }
+
+            ty.fmt(fmt)?;
+        } else {
+            self.ty.fmt(fmt)?;
+        }
+
+        bounds.extend(self.bounds.iter().cloned());
 
         if !parents.is_empty() {
             for (i, ty) in parents.iter().enumerate() {
@@ -109,17 +221,11 @@ impl TypeDef {
             }
         }
 
-        fmt_bounds(&self.bounds, fmt)?;
-
-        Ok(())
+        Ok(bounds)
     }
 
     fn fmt_attributes(&self, fmt: &mut Formatter) -> fmt::Result {
-        for attr in &self.attributes {
-            writeln!(fmt, "#[{}]", attr)?;
-        }
-
-        Ok(())
+        fmt.emit_attributes(&self.attributes)
     }
 
     fn fmt_allow(&self, fmt: &mut Formatter) -> fmt::Result {
@@ -162,3 +268,38 @@ impl TypeDef {
         Ok(())
     }
 }
+
+/// Split a generics string such as `"T, U: Into<Vec<u8>>"` on its top level
+/// commas.
+fn split_generics(generics: &str) -> Vec<&str> {
+    let mut ret = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in generics.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                ret.push(generics[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    ret.push(generics[start..].trim());
+    ret.retain(|param| !param.is_empty());
+    ret
+}
+
+/// Returns the argument naming a generic parameter, e.g., `T` for
+/// `T: Clone` and `N` for `const N: usize`.
+fn generic_arg(param: &str) -> &str {
+    let param = param.strip_prefix("const ").unwrap_or(param);
+
+    match param.find(':') {
+        Some(i) => param[..i].trim(),
+        None => param,
+    }
+}
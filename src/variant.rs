@@ -1,5 +1,6 @@
 use std::fmt::{self, Write};
 
+use crate::docs::Docs;
 use crate::fields::Fields;
 use crate::formatter::Formatter;
 
@@ -9,9 +10,14 @@ use crate::r#type::Type;
 #[derive(Debug, Clone)]
 pub struct Variant {
     name: String,
+    docs: Option<Docs>,
     fields: Fields,
+    /// Explicit discriminant value, e.g., `1`.
+    discriminant: Option<String>,
     /// Annotations for field e.g., `#[serde(rename = "variant")]`.
     annotations: Vec<String>,
+    /// Attributes for the variant, e.g., `#[cfg(feature = "std")]`.
+    attributes: Vec<String>,
 }
 
 impl Variant {
@@ -19,11 +25,35 @@ impl Variant {
     pub fn new(name: impl ToString) -> Self {
         Variant {
             name: name.to_string(),
+            docs: None,
             fields: Fields::Empty,
+            discriminant: None,
             annotations: Vec::new(),
+            attributes: Vec::new(),
         }
     }
 
+    /// Returns the name of the variant.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns `true` if the variant has no fields.
+    pub(crate) fn is_unit(&self) -> bool {
+        matches!(self.fields, Fields::Empty)
+    }
+
+    /// Returns the fields of the variant.
+    pub(crate) fn fields(&self) -> &Fields {
+        &self.fields
+    }
+
+    /// Set the variant documentation.
+    pub fn doc(&mut self, docs: impl ToString) -> &mut Self {
+        self.docs = Some(Docs::new(docs));
+        self
+    }
+
     /// Add a named field to the variant.
     pub fn named<T>(&mut self, name: impl ToString, ty: T) -> &mut Self
     where
@@ -39,20 +69,48 @@ impl Variant {
         self
     }
 
+    /// Set an explicit discriminant value for the variant, e.g., `Foo = 1`.
+    pub fn discriminant(&mut self, value: impl ToString) -> &mut Self {
+        self.discriminant = Some(value.to_string());
+        self
+    }
+
     /// Add an anotation to the variant.
     pub fn annotation(&mut self, annotation: impl Into<String>) -> &mut Self {
         self.annotations.push(annotation.into());
         self
     }
 
+    /// Add an attribute to the variant.
+    ///
+    /// ```
+    /// use codegen::Variant;
+    ///
+    /// let mut variant = Variant::new("Foo");
+    ///
+    /// // add a `#[cfg(feature = "std")]` attribute
+    /// variant.attr("cfg(feature = \"std\")");
+    /// ```
+    pub fn attr(&mut self, attr: impl ToString) -> &mut Self {
+        self.attributes.push(attr.to_string());
+        self
+    }
+
     /// Formats the variant using the given formatter.
     pub fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(ref docs) = self.docs {
+            docs.fmt(fmt)?;
+        }
         for a in &self.annotations {
             write!(fmt, "{}", a)?;
             writeln!(fmt)?;
         }
+        fmt.emit_attributes(&self.attributes)?;
         write!(fmt, "{}", self.name)?;
         self.fields.fmt(fmt)?;
+        if let Some(ref discriminant) = self.discriminant {
+            write!(fmt, " = {}", discriminant)?;
+        }
         writeln!(fmt, ",")?;
 
         Ok(())
@@ -1,3 +1,5 @@
+#![allow(deprecated)]
+
 use codegen::*;
 
 #[test]
@@ -410,11 +412,9 @@ mod foo {
 #[test]
 fn struct_mod_import() {
     let mut scope = Scope::new();
-    scope
-        .new_module("foo")
-        .import("bar", "Bar")
-        .new_struct("Foo")
-        .field("bar", "Bar");
+    let module = scope.new_module("foo");
+    module.import("bar", "Bar");
+    module.new_struct("Foo").field("bar", "Bar");
 
     let expect = r#"
 mod foo {
@@ -487,11 +487,11 @@ enum IpAddrKind {
 #[test]
 fn scoped_imports() {
     let mut scope = Scope::new();
-    scope
-        .new_module("foo")
-        .import("bar", "Bar")
-        .import("bar", "baz::Baz")
-        .import("bar::quux", "quuux::Quuuux")
+    let module = scope.new_module("foo");
+    module.import("bar", "Bar");
+    module.import("bar", "baz::Baz");
+    module.import("bar::quux", "quuux::Quuuux");
+    module
         .new_struct("Foo")
         .field("bar", "Bar")
         .field("baz", "baz::Baz")
@@ -737,3 +737,2979 @@ pub const MY_CONST: [usize; 4] = [1, 2, 3, 4  ];"#;
 
     assert_eq!(scope.to_string(), &expect[1..]);
 }
+
+#[test]
+fn struct_with_derive_where() {
+    let mut scope = Scope::new();
+
+    scope
+        .new_struct("Foo")
+        .generic("T")
+        .derive("PartialEq")
+        .derive_where(&["Clone", "Debug"], "T: Clone")
+        .derive_where(&["Default"], "")
+        .field("one", "T");
+
+    let expect = r#"
+#[derive(PartialEq)]
+#[derive_where(Clone, Debug; T: Clone)]
+#[derive_where(Default)]
+struct Foo<T> {
+    one: T,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn trait_object_unsafe() {
+    let mut scope = Scope::new();
+
+    let tr = scope.new_trait("Foo").vis("pub").object_unsafe(true);
+    tr.new_fn("bar").generic("T").arg_ref_self().arg("val", "T");
+
+    let expect = r#"
+// Not object safe
+pub trait Foo {
+    fn bar<T>(&self, val: T);
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn impl_with_stub_fns() {
+    let mut scope = Scope::new();
+    let imp = scope.new_impl("Bar");
+    imp.impl_trait("Foo");
+    imp.with_default_fn("baz").arg_ref_self().ret("usize");
+    imp.with_todo_fn("qux").arg_mut_self();
+
+    let expect = r#"
+impl Foo for Bar {
+    fn baz(&self) -> usize {
+        Default::default()
+    }
+
+    fn qux(&mut self) {
+        todo!()
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_returning_self() {
+    let mut scope = Scope::new();
+    let imp = scope.new_impl("Foo");
+    imp.new_fn("new").vis("pub").ret_self().line("Foo");
+    imp.new_fn("as_ref")
+        .arg_ref_self()
+        .ret_ref_self()
+        .line("self");
+    imp.new_fn("as_mut")
+        .arg_mut_self()
+        .ret_mut_ref_self()
+        .line("self");
+
+    let expect = r#"
+impl Foo {
+    pub fn new() -> Self {
+        Foo
+    }
+
+    fn as_ref(&self) -> &Self {
+        self
+    }
+
+    fn as_mut(&mut self) -> &mut Self {
+        self
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn enum_with_variant_attributes() {
+    let mut scope = Scope::new();
+    let e = scope.new_enum("Foo");
+    e.new_variant("Bar")
+        .annotation("#[serde(rename = \"bar\")]")
+        .attr("cfg(feature = \"std\")")
+        .attr("allow(unused)");
+    e.new_variant("Baz");
+
+    let expect = r#"
+enum Foo {
+    #[serde(rename = "bar")]
+    #[cfg(feature = "std")]
+    #[allow(unused)]
+    Bar,
+    Baz,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_summary() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo");
+    scope.new_struct("Bar");
+    scope.new_enum("Baz");
+    scope.new_impl("Foo");
+    scope.new_fn("qux");
+    scope.new_module("quux").new_struct("Nested");
+    scope.raw("// raw");
+
+    let summary = scope.summary();
+
+    assert_eq!(
+        summary,
+        ScopeSummary {
+            structs: 2,
+            enums: 1,
+            traits: 0,
+            impls: 1,
+            fns: 1,
+            modules: 1,
+            consts: 0,
+            type_aliases: 0,
+            raw_items: 1,
+            code_blocks: 0,
+            cfg_ifs: 0,
+        }
+    );
+}
+
+#[test]
+fn impl_with_generic_lifetimes() {
+    let mut scope = Scope::new();
+    scope
+        .new_impl("Foo")
+        .generic_lifetime("'a")
+        .generic_lifetime("'b: 'a")
+        .generic("T: 'a")
+        .target_generic("'a")
+        .target_generic("T")
+        .new_fn("bar")
+        .arg("val", "&'b T");
+
+    let expect = r#"
+impl<'a, 'b: 'a, T: 'a> Foo<'a, T> {
+    fn bar(val: &'b T) {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn iterator_impl() {
+    let mut scope = Scope::new();
+    scope.push_impl(Impl::iterator_impl(
+        "Counter",
+        "u32",
+        "self.count.checked_sub(1)",
+    ));
+
+    let expect = r#"
+impl Iterator for Counter {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.count.checked_sub(1)
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_returning_never() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("abort")
+        .ret_never()
+        .line("panic!(\"aborting\")");
+
+    let expect = r#"
+fn abort() -> ! {
+    panic!("aborting")
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+#[should_panic(expected = "async fns cannot return `!`")]
+fn async_fn_returning_never() {
+    let mut scope = Scope::new();
+    scope.new_fn("abort").set_async(true).ret_never();
+}
+
+#[test]
+#[should_panic(expected = "async fns cannot return `!`")]
+fn never_returning_fn_set_async() {
+    let mut scope = Scope::new();
+    scope.new_fn("abort").ret_never().set_async(true);
+}
+
+#[test]
+fn sealed_trait() {
+    let mut scope = Scope::new();
+
+    let mut tr = Trait::new("Foo");
+    tr.vis("pub");
+    let (tr, private) = tr.sealed();
+
+    scope.push_trait(tr);
+    scope.push_module(private);
+
+    let expect = r#"
+pub trait Foo: private::Sealed {
+}
+
+mod private {
+    pub trait Sealed {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn c_like_enum() {
+    let mut scope = Scope::new();
+
+    let mut e = Enum::new("Color");
+    e.vis("pub").derive("Clone").derive("Copy");
+    e.new_variant("Red").discriminant(1);
+    e.new_variant("Green").discriminant(2);
+    e.new_variant("Blue");
+
+    let impls = e.c_like();
+    scope.push_enum(e);
+    for imp in impls {
+        scope.push_impl(imp);
+    }
+
+    let expect = r#"
+#[derive(Clone, Copy)]
+#[repr(u8)]
+pub enum Color {
+    Red = 1,
+    Green = 2,
+    Blue,
+}
+
+impl TryFrom<u8> for Color {
+    type Error = u8;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            v if v == Color::Red as u8 => Ok(Color::Red),
+            v if v == Color::Green as u8 => Ok(Color::Green),
+            v if v == Color::Blue as u8 => Ok(Color::Blue),
+            _ => Err(value),
+        }
+    }
+}
+
+impl From<Color> for u8 {
+    fn from(value: Color) -> u8 {
+        value as u8
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+#[should_panic(expected = "c-like enums must only have unit variants")]
+fn c_like_enum_with_fields() {
+    let mut e = Enum::new("Foo");
+    e.new_variant("Bar").tuple("u8");
+
+    e.c_like();
+}
+
+#[test]
+fn const_with_literal_values() {
+    let mut scope = Scope::new();
+
+    scope
+        .new_const("i64")
+        .name("INT")
+        .value_lit(LiteralValue::Int(-42));
+    scope
+        .new_const("f64")
+        .name("FLOAT")
+        .value_lit(LiteralValue::Float(1.0));
+    scope
+        .new_const("bool")
+        .name("BOOL")
+        .value_lit(LiteralValue::Bool(true));
+    scope
+        .new_const("&str")
+        .name("STR")
+        .value_lit(LiteralValue::Str("say \"hi\"\n".to_string()));
+    scope
+        .new_const("&[u8]")
+        .name("BYTES")
+        .value_lit(LiteralValue::Bytes(vec![b'a', b'"', 0xff]));
+
+    let expect = r#"
+const INT: i64 = -42;
+
+const FLOAT: f64 = 1.0;
+
+const BOOL: bool = true;
+
+const STR: &str = "say \"hi\"\n";
+
+const BYTES: &[u8] = b"a\"\xff";"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn const_with_special_float_values() {
+    let mut scope = Scope::new();
+
+    scope
+        .new_const("f32")
+        .name("NAN")
+        .value_lit(LiteralValue::Float(f64::NAN));
+    scope
+        .new_const("f32")
+        .name("INF")
+        .value_lit(LiteralValue::Float(f64::INFINITY));
+    scope
+        .new_const("f64")
+        .name("NEG_INF")
+        .value_lit(LiteralValue::Float(f64::NEG_INFINITY));
+
+    let expect = r#"
+const NAN: f32 = f32::NAN;
+
+const INF: f32 = f32::INFINITY;
+
+const NEG_INF: f64 = f64::NEG_INFINITY;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_with_test_cases() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("adds")
+        .test_case(&[1, 2, 3])
+        .test_case(&["-1", "1", "0"])
+        .arg("a", "i32")
+        .arg("b", "i32")
+        .arg("sum", "i32")
+        .line("assert_eq!(a + b, sum);");
+
+    let expect = r#"
+#[test_case(1, 2, 3)]
+#[test_case(-1, 1, 0)]
+fn adds(a: i32, b: i32, sum: i32) {
+    assert_eq!(a + b, sum);
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_clone_without_raw_items() {
+    let mut scope = Scope::new();
+    scope.import("std::fmt", "Debug");
+    scope.raw("// generated at build time");
+    scope.new_struct("Foo");
+    scope.raw("// trailer");
+
+    let expect = r#"
+use std::fmt::Debug;
+
+struct Foo;"#;
+
+    assert_eq!(scope.clone_without_raw_items().to_string(), &expect[1..]);
+    assert_eq!(
+        scope.clone_only_imports().to_string(),
+        "use std::fmt::Debug;\n"
+    );
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn trait_alias() {
+    let mut scope = Scope::new();
+    scope
+        .new_trait_alias("SendSync", ["Send", "Sync"])
+        .vis("pub")
+        .doc("Thread safe types");
+
+    let expect = r#"
+/// Thread safe types
+pub trait SendSync = Send + Sync;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_with_aliased_imports() {
+    let mut scope = Scope::new();
+    scope.import("std::fmt", "Debug");
+    scope.import_as("std::fmt", "Result", "FmtResult");
+    scope.import_as("std::io", "Result", "IoResult").vis("pub");
+
+    let expect = r#"
+use std::fmt::{Debug, Result as FmtResult};
+pub use std::io::Result as IoResult;
+"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+
+    let config = FormatterConfig {
+        edition: RustEdition::Edition2024,
+        ..Default::default()
+    };
+
+    assert_eq!(scope.fmt_with_config(&config), &expect[1..]);
+}
+
+#[test]
+fn code_block_in_mod() {
+    let mut scope = Scope::new();
+
+    scope.new_module("foo").scope().code_block(
+        r#"
+            macro_rules! answer {
+                () => {
+                    42
+                };
+            }
+        "#,
+    );
+
+    let expect = r#"
+mod foo {
+    macro_rules! answer {
+        () => {
+            42
+        };
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[cfg(feature = "specialization")]
+#[test]
+fn impl_with_default_fn() {
+    let mut scope = Scope::new();
+    let imp = scope.new_impl("T");
+    imp.generic("T").impl_trait("Foo");
+    imp.new_fn("foo")
+        .default_fn(true)
+        .arg_ref_self()
+        .line("println!(\"generic\");");
+
+    let expect = r#"
+impl<T> Foo for T {
+    default fn foo(&self) {
+        println!("generic");
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_with_visibility_fields() {
+    let mut scope = Scope::new();
+
+    let mut private = Field::new("four", "u8");
+    private.vis("pub(crate)");
+
+    scope
+        .new_struct("Foo")
+        .vis("pub")
+        .field("one", "u8")
+        .visibility_fields("pub")
+        .field("two", "u8")
+        .push_field(Field::new("three", "u8"))
+        .push_field(private);
+
+    let expect = r#"
+pub struct Foo {
+    pub one: u8,
+    pub two: u8,
+    pub three: u8,
+    pub(crate) four: u8,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_with_self_imports() {
+    let mut scope = Scope::new();
+    scope.import("std::io", "Read");
+    scope.import("std::io", "Write");
+    scope.import_self("std::io");
+    scope.import_self("std::fmt");
+    scope.import_self("std::sync::atomic").alias = Some("atomics".to_string());
+
+    let expect = r#"
+use std::io::{self, Read, Write};
+use std::fmt;
+use std::sync::atomic as atomics;
+"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn enum_with_documented_variants() {
+    let mut scope = Scope::new();
+    let e = scope.new_enum("Shape").doc("A shape");
+    e.new_variant("Circle")
+        .doc("A circle\nwith a radius")
+        .tuple("f64");
+    e.new_variant("Point").doc("A point");
+
+    let expect = r#"
+/// A shape
+enum Shape {
+    /// A circle
+    /// with a radius
+    Circle(f64),
+    /// A point
+    Point,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn fn_with_pattern_args() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("add")
+        .arg_pattern("(x, y)", "(u32, u32)")
+        .arg_pattern("Wrapper(z)", "Wrapper")
+        .ret("u32")
+        .line("x + y + z");
+
+    let expect = r#"
+fn add((x, y): (u32, u32), Wrapper(z): Wrapper) -> u32 {
+    x + y + z
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_with_const_block() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo").tuple_field("u32");
+    scope.const_block("assert!(std::mem::size_of::<Foo>() == 4);");
+
+    let expect = r#"
+struct Foo(u32);
+
+const _: () = {
+    assert!(std::mem::size_of::<Foo>() == 4);
+};"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn impl_with_bounded_associated_type() {
+    let mut scope = Scope::new();
+    scope
+        .new_impl("Foo")
+        .impl_trait("Bar")
+        .associate_type_with_bounds(
+            "Iter",
+            "std::vec::IntoIter<u8>",
+            vec![Type::new("Iterator<Item = u8>"), Type::new("Send")],
+        )
+        .associate_type("Item", "u8");
+
+    let expect = r#"
+impl Bar for Foo {
+    type Iter: Iterator<Item = u8> + Send = std::vec::IntoIter<u8>;
+    type Item = u8;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn pushed_type_alias_in_mod() {
+    let mut scope = Scope::new();
+
+    let mut alias = TypeAlias::new("Result", "std::result::Result");
+    alias
+        .vis("pub")
+        .generic("T")
+        .doc("Result with a fixed error type");
+    alias.set_ty({
+        let mut ty = Type::new("std::result::Result");
+        ty.generic("T").generic("Error");
+        ty
+    });
+
+    scope.new_module("foo").push_type_alias(alias);
+
+    let expect = r#"
+mod foo {
+    /// Result with a fixed error type
+    pub type Result<T> = std::result::Result<T, Error>;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_hash_impl() {
+    let mut scope = Scope::new();
+
+    let mut s = Struct::new("Foo");
+    s.generic("T").field("id", "u32").field("value", "T");
+    s.new_field("cache", "Option<u64>").skip_hash(true);
+
+    let imp = s.generate_hash_impl();
+    scope.push_struct(s);
+    scope.push_impl(imp);
+
+    let expect = r#"
+struct Foo<T> {
+    id: u32,
+    value: T,
+    cache: Option<u64>,
+}
+
+impl<T> std::hash::Hash for Foo<T>
+where T: std::hash::Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+        self.value.hash(state);
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_hash_impl_bounded_generics() {
+    let mut scope = Scope::new();
+
+    let mut s = Struct::new("Foo");
+    s.generic("'a")
+        .generic("T: Clone")
+        .generic("const N: usize")
+        .bound("T", "Send")
+        .field("value", "&'a [T; N]");
+
+    scope.push_impl(s.generate_hash_impl());
+
+    let expect = r#"
+impl<'a, T: Clone, const N: usize> std::hash::Hash for Foo<'a, T, N>
+where T: Send,
+      T: std::hash::Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_partial_eq_impl() {
+    let mut scope = Scope::new();
+
+    let mut s = Struct::new("Foo");
+    s.field("id", "u32")
+        .field("name", "String")
+        .field("updated_at", "u64");
+
+    let imp = s.generate_partial_eq_impl(&["updated_at"]);
+    scope.push_struct(s);
+    scope.push_impl(imp);
+
+    let mut bar = Struct::new("Bar");
+    scope.push_impl(bar.tuple_field("u64").generate_partial_eq_impl(&["0"]));
+
+    let mut pair = Struct::new("Pair");
+    pair.generic("T")
+        .generic("U: Clone")
+        .tuple_field("T")
+        .tuple_field("U");
+    scope.push_impl(pair.generate_partial_eq_impl(&[]));
+
+    let expect = r#"
+struct Foo {
+    id: u32,
+    name: String,
+    updated_at: u64,
+}
+
+impl PartialEq for Foo {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id && self.name == other.name
+    }
+}
+
+impl PartialEq for Bar {
+    fn eq(&self, other: &Self) -> bool {
+        true
+    }
+}
+
+impl<T, U: Clone> PartialEq for Pair<T, U>
+where T: PartialEq,
+      U: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 && self.1 == other.1
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_into_module() {
+    let mut inner = Scope::new();
+    inner.import("std::fmt", "Debug");
+    inner.new_struct("Foo").derive("Debug");
+
+    let mut scope = Scope::new();
+    scope.push_module(inner.into_module("foo"));
+
+    let expect = r#"
+mod foo {
+    use std::fmt::Debug;
+
+    #[derive(Debug)]
+    struct Foo;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_with_rust_abi() {
+    let mut scope = Scope::new();
+    scope.new_fn("foo").extern_abi("Rust").line("bar()");
+
+    let expect = r#"
+extern "Rust" fn foo() {
+    bar()
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+
+    let config = FormatterConfig {
+        omit_rust_abi: true,
+        ..Default::default()
+    };
+
+    let expect = r#"
+fn foo() {
+    bar()
+}"#;
+
+    assert_eq!(scope.fmt_with_config(&config), &expect[1..]);
+}
+
+#[test]
+fn function_bodyless_with_omitted_rust_abi() {
+    let mut scope = Scope::new();
+    scope.new_fn("foo").set_extern("Rust");
+
+    let expect = r#"
+extern "Rust" fn foo();"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+
+    let config = FormatterConfig {
+        omit_rust_abi: true,
+        ..Default::default()
+    };
+
+    let expect = r#"
+fn foo() {
+}"#;
+
+    assert_eq!(scope.fmt_with_config(&config), &expect[1..]);
+}
+
+#[test]
+fn formatter_begin_end_line() {
+    use std::fmt::Write;
+
+    let mut dst = String::new();
+    let mut fmt = Formatter::new(&mut dst);
+
+    fmt.begin_line();
+    fmt.end_line();
+    fmt.indent(|fmt| {
+        fmt.begin_line();
+        fmt.write_str("foo").unwrap();
+        fmt.begin_line();
+        fmt.end_line();
+    });
+
+    assert_eq!(dst, "\n    foo\n");
+}
+
+#[test]
+fn scope_with_cfg_if() {
+    let mut unix = Scope::new();
+    unix.new_fn("imp").line("unix()");
+
+    let mut windows = Scope::new();
+    windows.new_fn("imp").line("windows()");
+
+    let mut fallback = Scope::new();
+    fallback.new_fn("imp").line("unimplemented!()");
+
+    let mut scope = Scope::new();
+    scope
+        .new_cfg_if()
+        .arm("unix", unix)
+        .arm("windows", windows)
+        .else_arm(fallback);
+
+    let expect = r#"
+cfg_if::cfg_if! {
+    if #[cfg(unix)] {
+        fn imp() {
+            unix()
+        }
+    } else if #[cfg(windows)] {
+        fn imp() {
+            windows()
+        }
+    } else {
+        fn imp() {
+            unimplemented!()
+        }
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_with_inner_limits() {
+    let mut scope = Scope::new();
+    scope.recursion_limit(256).type_length_limit(1048576);
+    scope.import("std::fmt", "Debug");
+
+    let expect = r#"
+#![recursion_limit = "256"]
+#![type_length_limit = "1048576"]
+
+use std::fmt::Debug;
+"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_with_inner_limits_set_twice() {
+    let mut scope = Scope::new();
+    scope
+        .recursion_limit(128)
+        .attr_inner("allow(dead_code)")
+        .type_length_limit(1024)
+        .recursion_limit(256)
+        .type_length_limit(1048576);
+
+    let expect = r#"
+#![recursion_limit = "256"]
+#![allow(dead_code)]
+#![type_length_limit = "1048576"]"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_with_field_groups() {
+    let mut scope = Scope::new();
+    scope
+        .new_struct("Config")
+        .field("name", "String")
+        .add_field_group(
+            "Network",
+            vec![Field::new("host", "String"), Field::new("port", "u16")],
+        )
+        .add_field_group("Limits", vec![Field::new("max_conns", "usize")]);
+
+    let expect = r#"
+struct Config {
+    name: String,
+    // Network
+    host: String,
+    port: u16,
+    // Limits
+    max_conns: usize,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn impl_with_op() {
+    let mut scope = Scope::new();
+    scope
+        .new_impl("Meters")
+        .add_op("Add", "Meters", "Meters", "Meters(self.0 + rhs.0)");
+    scope
+        .new_impl("Flags")
+        .add_op("BitAnd", "u8", "Flags", "Flags(self.0 & rhs)");
+
+    let expect = r#"
+impl std::ops::Add<Meters> for Meters {
+    type Output = Meters;
+
+    fn add(self, rhs: Meters) -> Self::Output {
+        Meters(self.0 + rhs.0)
+    }
+}
+
+impl std::ops::BitAnd<u8> for Flags {
+    type Output = Flags;
+
+    fn bitand(self, rhs: u8) -> Self::Output {
+        Flags(self.0 & rhs)
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+#[should_panic(expected = "`Neg` is not a binary `std::ops` operator")]
+fn impl_add_op_unary() {
+    Impl::new("Meters").add_op("Neg", "Meters", "Meters", "Meters(-self.0)");
+}
+
+#[test]
+#[should_panic(expected = "impl block already implements a trait")]
+fn impl_add_op_twice() {
+    Impl::new("Meters")
+        .add_op("Add", "Meters", "Meters", "Meters(self.0 + rhs.0)")
+        .add_op("Sub", "Meters", "Meters", "Meters(self.0 - rhs.0)");
+}
+
+#[test]
+fn module_with_inner_attributes() {
+    let mut scope = Scope::new();
+    let module = scope.new_module("tests");
+    module.attr_inner("allow(dead_code)").import("super", "*");
+    module.new_struct("Fixture");
+
+    let expect = r#"
+mod tests {
+    #![allow(dead_code)]
+
+    use super::*;
+
+    struct Fixture;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_with_lifetime_return() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("name")
+        .generic("'a")
+        .arg("foo", "&'a Foo")
+        .ret_lifetime("a", "str", false)
+        .line("&foo.name");
+
+    let mut items = Type::new("Vec");
+    items.generic("u8");
+
+    scope
+        .new_fn("items")
+        .generic("'a")
+        .arg("foo", "&'a mut Foo")
+        .ret_lifetime("'a", items, true)
+        .line("&mut foo.items");
+
+    let expect = r#"
+fn name<'a>(foo: &'a Foo) -> &'a str {
+    &foo.name
+}
+
+fn items<'a>(foo: &'a mut Foo) -> &'a mut Vec<u8> {
+    &mut foo.items
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn trait_with_gat_where_bounds() {
+    let mut scope = Scope::new();
+    let tr = scope.new_trait("Container");
+    tr.associated_type("Item<'a>")
+        .bound("Debug")
+        .where_bound("Self", "'a");
+    tr.associated_type("Iter<'a, T>")
+        .where_bound("Self", "'a")
+        .where_bound("T", "Clone");
+
+    let expect = r#"
+trait Container {
+    type Item<'a>: Debug where Self: 'a;
+    type Iter<'a, T> where Self: 'a, T: Clone;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_emptiness() {
+    let mut scope = Scope::new();
+    assert!(scope.is_empty());
+    assert!(!scope.has_imports());
+    assert!(!scope.has_items());
+
+    scope.import("std::fmt", "Debug");
+    assert!(!scope.is_empty());
+    assert!(scope.has_imports());
+    assert!(!scope.has_items());
+
+    scope.new_struct("Foo");
+    assert!(scope.has_items());
+}
+
+#[test]
+fn impl_target_name() {
+    let mut imp = Impl::new("Foo");
+    imp.target_generic("T");
+    assert_eq!(imp.target_name(), "Foo");
+
+    let imp = Impl::new("Bar<'a, T>");
+    assert_eq!(imp.target_name(), "Bar");
+}
+
+#[test]
+fn enum_generate_constructors() {
+    let mut foo = Enum::new("Foo");
+    foo.new_variant("Bar").tuple("u32");
+    foo.new_variant("Baz");
+    foo.new_variant("HTTPPoint").tuple("i32").tuple("i32");
+    foo.new_variant("Named")
+        .named("id", "u64")
+        .named("name", "String");
+
+    let mut scope = Scope::new();
+    let imp = scope.new_impl("Foo");
+    for func in foo.generate_constructors() {
+        imp.push_fn(func);
+    }
+
+    let expect = r#"
+impl Foo {
+    fn bar(val: u32) -> Foo {
+        Foo::Bar(val)
+    }
+
+    fn baz() -> Foo {
+        Foo::Baz
+    }
+
+    fn http_point(val0: i32, val1: i32) -> Foo {
+        Foo::HTTPPoint(val0, val1)
+    }
+
+    fn named(id: u64, name: String) -> Foo {
+        Foo::Named { id, name }
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn enum_generate_constructors_keywords() {
+    let mut token = Enum::new("Token");
+    token.new_variant("Fn");
+    token.new_variant("Type").tuple("String");
+    token.new_variant("Ident").tuple("String");
+
+    let mut scope = Scope::new();
+    let imp = scope.new_impl("Token");
+    for func in token.generate_constructors() {
+        imp.push_fn(func);
+    }
+
+    let expect = r#"
+impl Token {
+    fn r#fn() -> Token {
+        Token::Fn
+    }
+
+    fn r#type(val: String) -> Token {
+        Token::Type(val)
+    }
+
+    fn ident(val: String) -> Token {
+        Token::Ident(val)
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+#[should_panic(expected = "`self` cannot be a raw identifier")]
+fn enum_generate_constructors_self() {
+    let mut foo = Enum::new("Foo");
+    foo.new_variant("Bar");
+    foo.new_variant("SELF");
+    foo.generate_constructors();
+}
+
+#[test]
+fn function_with_default_args() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("connect")
+        .doc("Connect to the server.")
+        .arg("host", "&str")
+        .arg_with_default("port", "u16", "8080")
+        .arg_with_default("timeout", "Duration", "Duration::from_secs(30)")
+        .line("let port = port.unwrap_or(8080);");
+
+    let expect = r#"
+/// Connect to the server.
+///
+/// Default for `port`: `8080`
+/// Default for `timeout`: `Duration::from_secs(30)`
+fn connect(host: &str, port: Option<u16>, timeout: Option<Duration>) {
+    let port = port.unwrap_or(8080);
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_with_visible_imports() {
+    let mut scope = Scope::new();
+    scope.import_as_vis("crate::error", "Error", "pub(crate)");
+    scope.pub_import("crate::types", "Foo");
+    scope.pub_import("crate::types", "Bar");
+
+    let expect = r#"
+pub(crate) use crate::error::Error;
+pub use crate::types::{Foo, Bar};
+"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn trait_notable() {
+    let mut scope = Scope::new();
+    scope.new_trait("Future").attr("must_use").notable_trait();
+
+    let expect = r#"
+#[doc(notable_trait)]
+#[must_use]
+trait Future {
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn module_with_attributes() {
+    let mut scope = Scope::new();
+    scope
+        .new_module("tests")
+        .attr("cfg(test)")
+        .attr("allow(unused)")
+        .new_struct("Fixture");
+
+    let expect = r#"
+#[cfg(test)]
+#[allow(unused)]
+mod tests {
+    struct Fixture;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_with_link_name() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("c_abs")
+        .link_name("abs")
+        .line("unimplemented!()");
+
+    let expect = r#"
+#[link_name = "abs"]
+fn c_abs() {
+    unimplemented!()
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_with_never_and_unit_types() {
+    let mut scope = Scope::new();
+    scope.new_fn("run").ret(Type::unit()).line("()");
+    scope
+        .new_fn("abort")
+        .arg("cb", "fn() -> !")
+        .ret(Type::never())
+        .line("cb()");
+
+    let expect = r#"
+fn run() -> () {
+    ()
+}
+
+fn abort(cb: fn() -> !) -> ! {
+    cb()
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn struct_to_json_schema() {
+    let mut s = Struct::new("User");
+    s.new_field("id", "u64").doc("Unique identifier");
+    s.field("name", "String");
+    s.field("email", "Option<String>");
+    s.field("tags", "Vec<String>");
+    s.field("scores", "std::collections::HashMap<String, f64>");
+    s.field("address", "Address");
+
+    let expect = serde_json::json!({
+        "title": "User",
+        "type": "object",
+        "properties": {
+            "id": { "type": "integer", "description": "Unique identifier" },
+            "name": { "type": "string" },
+            "email": { "type": "string" },
+            "tags": { "type": "array", "items": { "type": "string" } },
+            "scores": {
+                "type": "object",
+                "additionalProperties": { "type": "number" }
+            },
+            "address": { "$ref": "#/$defs/Address" },
+        },
+        "required": ["id", "name", "tags", "scores", "address"],
+    });
+
+    assert_eq!(s.to_json_schema(), expect);
+}
+
+#[test]
+fn scope_write_to_dir() {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("codegen-write-{}-{}", std::process::id(), nanos));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut scope = Scope::new();
+    scope.import_as("std::fmt", "Result", "FmtResult");
+    scope.new_fn("foo").ret("FmtResult").line("Ok(())");
+    scope.new_fn("bar");
+
+    let path = scope
+        .write_to_dir(&dir, "plain", &WriteConfig::default())
+        .unwrap();
+    assert_eq!(path, dir.join("plain.rs"));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "use std::fmt::Result as FmtResult;\n\nfn foo() -> FmtResult {\n    Ok(())\n}\n\nfn bar() {\n}\n"
+    );
+
+    // The rustfmt half of the test needs a `rustfmt` binary
+    let has_rustfmt = std::process::Command::new("rustfmt")
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success());
+
+    if has_rustfmt {
+        let config = WriteConfig {
+            rust_edition: RustEdition::Edition2024,
+            rustfmt: true,
+        };
+        let path = scope.write_to_dir(&dir, "formatted.rs", &config).unwrap();
+        assert_eq!(path, dir.join("formatted.rs"));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "use std::fmt::Result as FmtResult;\n\nfn foo() -> FmtResult {\n    Ok(())\n}\n\nfn bar() {}\n"
+        );
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn impl_push_assoc_type_bound() {
+    let mut scope = Scope::new();
+    let imp = scope.new_impl("Foo");
+    imp.impl_trait("Container")
+        .push_assoc_type_bound("Item", "Clone")
+        .associate_type("Item", "String")
+        .push_assoc_type_bound("Item", "Send")
+        .associate_type_with_bounds("Key", "u32", vec!["Copy".into()])
+        .push_assoc_type_bound("Key", "Hash");
+
+    let expect = r#"
+impl Container for Foo {
+    type Item: Clone + Send = String;
+    type Key: Copy + Hash = u32;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn import_alias() {
+    let mut scope = Scope::new();
+    scope
+        .import("serde", "Deserialize")
+        .alias("DeserializeTrait");
+    scope.import("serde", "Serialize").alias("Serialize");
+
+    let module = scope.new_module("foo");
+    module.import("std::io", "Result").alias("IoResult");
+    module.new_fn("read").ret("IoResult<()>").line("Ok(())");
+
+    let expect = r#"
+use serde::{Deserialize as DeserializeTrait, Serialize};
+
+mod foo {
+    use std::io::Result as IoResult;
+
+    fn read() -> IoResult<()> {
+        Ok(())
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn lifetime_where_bounds() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("shorten")
+        .generic("'a")
+        .generic("'b")
+        .arg("s", "&'a str")
+        .ret("&'b str")
+        .lifetime_bound("'a", "'b")
+        .line("s");
+    scope
+        .new_impl("Foo")
+        .generic_lifetime("'a")
+        .generic_lifetime("'b")
+        .target_generic("'a")
+        .target_generic("'b")
+        .lifetime_bound("b", "a");
+
+    let expect = r#"
+fn shorten<'a, 'b>(s: &'a str) -> &'b str
+where 'a: 'b,
+{
+    s
+}
+
+impl<'a, 'b> Foo<'a, 'b>
+where 'b: 'a,
+{
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_to_bytes() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo");
+
+    assert_eq!(scope.to_bytes(), b"struct Foo;");
+    assert_eq!(scope.to_bytes_bom(), b"\xEF\xBB\xBFstruct Foo;");
+}
+
+#[test]
+fn scope_with_glob_imports() {
+    let mut scope = Scope::new();
+    scope.import("std::io", "Error");
+    scope.import_glob("std::io");
+    scope.import("std::io", "Result");
+    scope.import_glob("std::io::prelude");
+    scope.import_glob("std::fmt");
+    scope.import("std::fmt", "Write");
+    scope.import_glob("crate::types").vis("pub");
+
+    let module = scope.new_module("tests");
+    module.import_glob("super");
+    module.new_struct("Fixture");
+
+    let expect = r#"
+use std::io::{Error, Result};
+use std::io::*;
+use std::io::prelude::*;
+use std::fmt::Write;
+use std::fmt::*;
+pub use crate::types::*;
+
+mod tests {
+    use super::*;
+
+    struct Fixture;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_remove_import() {
+    let mut scope = Scope::new();
+    scope.import("std::collections", "HashMap");
+    scope.import("std::collections", "HashSet");
+    scope.import("std::io", "prelude::Read");
+
+    assert!(scope.has_import("std::collections", "HashMap"));
+    assert!(scope.has_import("std::io", "prelude::Write"));
+    assert!(!scope.has_import("std::collections", "BTreeMap"));
+
+    assert!(scope.remove_import("std::collections", "HashMap"));
+    assert!(!scope.remove_import("std::collections", "HashMap"));
+    assert!(!scope.remove_import("std::fmt", "Debug"));
+    assert!(!scope.has_import("std::collections", "HashMap"));
+
+    let expect = r#"
+use std::collections::HashSet;
+use std::io::prelude;
+"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn item_counts() {
+    let mut e = Enum::new("Color");
+    assert!(e.is_fieldless());
+    e.new_variant("Red");
+    e.new_variant("Green");
+    assert!(e.is_fieldless());
+    assert_eq!(e.variant_count(), 2);
+    e.new_variant("Rgb").tuple("u8").tuple("u8").tuple("u8");
+    assert!(!e.is_fieldless());
+    assert_eq!(e.variant_count(), 3);
+
+    let mut s = Struct::new("Foo");
+    assert_eq!(s.field_count(), 0);
+    s.field("one", "usize").field("two", "String");
+    assert_eq!(s.field_count(), 2);
+
+    let mut t = Struct::new("Bar");
+    t.tuple_field("u8");
+    assert_eq!(t.field_count(), 1);
+}
+
+#[test]
+fn trait_blanket_impl() {
+    let mut scope = Scope::new();
+
+    let mut tr = Trait::new("DisplayExt");
+    tr.new_fn("shout").arg_ref_self().ret("String");
+
+    let mut bound = Type::new("Into");
+    bound.generic("String");
+
+    scope.push_impl(tr.generate_blanket_impl("std::fmt::Display"));
+    scope.push_impl(tr.generate_blanket_impl(bound));
+
+    let expect = r#"
+impl<T: std::fmt::Display> DisplayExt for T {
+}
+
+impl<T: Into<String>> DisplayExt for T {
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn trait_blanket_impl_generic_trait() {
+    let mut scope = Scope::new();
+
+    let mut tr = Trait::new("Convert");
+    tr.generic("'a").generic("T: Clone").bound("T", "Send");
+    tr.new_fn("convert").arg_ref_self().ret("&'a T");
+
+    scope.push_impl(tr.generate_blanket_impl("Clone"));
+
+    let expect = r#"
+impl<'a, T: Clone, T0: Clone> Convert<'a, T> for T0
+where T: Send,
+{
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_item_lookup() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo").field("one", "usize");
+    scope.new_enum("Bar").new_variant("Baz");
+    scope.new_trait("Qux");
+    scope.new_fn("quux");
+    scope.new_impl("Foo").target_generic("T");
+    scope.new_const("usize").name("MAX").value("10");
+    scope.new_type_alias("Alias", "Foo");
+
+    assert!(scope.get_struct("Foo").is_some());
+    assert!(scope.get_struct("Bar").is_none());
+    assert!(scope.get_enum("Bar").is_some());
+    assert!(scope.get_trait("Qux").is_some());
+    assert!(scope.get_fn("quux").is_some());
+    assert!(scope.get_impl("Foo").is_some());
+    assert!(scope.get_const("MAX").is_some());
+    assert!(scope.get_type_alias("Alias").is_some());
+
+    scope.get_struct_mut("Foo").unwrap().field("two", "String");
+    scope.get_fn_mut("quux").unwrap().line("todo!()");
+    scope.get_impl_mut("Foo").unwrap().generic("T");
+
+    let expect = r#"
+struct Foo {
+    one: usize,
+    two: String,
+}
+
+enum Bar {
+    Baz,
+}
+
+trait Qux {
+}
+
+fn quux() {
+    todo!()
+}
+
+impl<T> Foo<T> {
+}
+
+const MAX: usize = 10;
+
+type Alias = Foo;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_item_removal() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo");
+    scope.new_enum("Bar");
+    scope.new_fn("baz");
+    scope.new_trait("Qux");
+    scope.new_impl("Foo");
+    scope.new_const("usize").name("MAX").value("10");
+    scope.new_type_alias("Alias", "Foo");
+    scope.new_struct("Last");
+
+    assert!(scope.remove_struct("Missing").is_none());
+    assert!(scope.remove_impl("Bar").is_none());
+
+    assert_eq!(scope.remove_struct("Foo").unwrap().ty().name(), "Foo");
+    assert!(scope.remove_fn("baz").is_some());
+    assert!(scope.remove_impl("Foo").is_some());
+    assert!(scope.remove_const("MAX").is_some());
+    assert!(scope.remove_fn("baz").is_none());
+
+    let expect = r#"
+enum Bar {
+}
+
+trait Qux {
+}
+
+type Alias = Foo;
+
+struct Last;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+
+    assert!(scope.remove_enum("Bar").is_some());
+    assert!(scope.remove_trait("Qux").is_some());
+    assert!(scope.remove_type_alias("Alias").is_some());
+
+    assert_eq!(scope.to_string(), "struct Last;");
+}
+
+#[test]
+fn scope_merge_imports() {
+    let mut a = Scope::new();
+    a.import("std::fmt", "Debug");
+    a.import("std::io", "Read").vis("pub");
+
+    let mut b = Scope::new();
+    b.import("std::io", "Read");
+    b.import("std::io", "Write");
+    b.import("std::collections", "HashMap");
+    b.new_struct("Ignored");
+
+    let imports = b.clone_imports();
+    assert_eq!(imports.len(), 2);
+    assert_eq!(imports["std::io"].len(), 2);
+
+    a.merge_imports(&b);
+
+    let expect = r#"
+use std::fmt::Debug;
+use std::io::Write;
+use std::collections::HashMap;
+pub use std::io::Read;
+"#;
+
+    assert_eq!(a.to_string(), &expect[1..]);
+}
+
+#[test]
+fn attribute_at_position() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("foo")
+        .attr("inline")
+        .attr("doc = \"Foo\"")
+        .attribute_at(1, "must_use");
+    scope
+        .new_struct("Bar")
+        .attr("non_exhaustive")
+        .attribute_at(0, "cfg(test)");
+    scope.new_enum("Baz").attribute_at(0, "non_exhaustive");
+    scope
+        .new_impl("Bar")
+        .r#macro("#[async_trait]")
+        .attribute_at(0, "cfg(test)");
+    scope
+        .new_module("qux")
+        .attr("allow(unused)")
+        .attribute_at(0, "cfg(test)");
+
+    let expect = r#"
+#[inline]
+#[must_use]
+#[doc = "Foo"]
+fn foo() {
+}
+
+#[cfg(test)]
+#[non_exhaustive]
+struct Bar;
+
+#[non_exhaustive]
+enum Baz {
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Bar {
+}
+
+#[cfg(test)]
+#[allow(unused)]
+mod qux {
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_item_iterators() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo");
+    scope.new_enum("Bar");
+    scope.new_struct("Baz");
+    scope.new_fn("qux");
+    scope.new_impl("Foo");
+    scope.new_trait("Quux");
+    scope.new_const("usize").name("MAX").value("10");
+
+    let module = scope.new_module("inner");
+    module.new_struct("Nested");
+    assert_eq!(module.structs().count(), 1);
+
+    let names: Vec<&str> = scope.structs().map(|s| s.ty().name()).collect();
+    assert_eq!(names, ["Foo", "Baz"]);
+    assert_eq!(scope.enums().count(), 1);
+    assert_eq!(scope.traits().count(), 1);
+    assert_eq!(scope.fns().count(), 1);
+    assert_eq!(scope.impls().count(), 1);
+    assert_eq!(scope.consts().count(), 1);
+    assert_eq!(scope.modules().count(), 1);
+
+    for s in scope.structs_mut() {
+        s.derive("Debug");
+    }
+    for m in scope.modules_mut() {
+        for s in m.structs_mut() {
+            s.vis("pub");
+        }
+    }
+
+    let expect = r#"
+#[derive(Debug)]
+struct Foo;
+
+enum Bar {
+}
+
+#[derive(Debug)]
+struct Baz;
+
+fn qux() {
+}
+
+impl Foo {
+}
+
+trait Quux {
+}
+
+const MAX: usize = 10;
+
+mod inner {
+    pub struct Nested;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_replace_item() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo").field("old", "u8");
+    scope.new_fn("bar");
+    scope.new_impl("Foo");
+
+    let mut foo = Struct::new("Foo");
+    foo.field("new", "u16");
+
+    let old = scope.replace_item("Foo", Item::Struct(foo)).unwrap();
+    assert_eq!(old.name(), Some("Foo"));
+    assert!(matches!(old, Item::Struct(_)));
+
+    assert!(scope
+        .replace_item("missing", Item::Raw("// unused".to_string()))
+        .is_none());
+
+    let expect = r#"
+struct Foo {
+    new: u16,
+}
+
+fn bar() {
+}
+
+impl Foo {
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_is_async() {
+    let mut scope = Scope::new();
+
+    let f = scope.new_fn("fetch");
+    assert!(!f.is_async());
+    f.set_async(true);
+    assert!(f.is_async());
+
+    scope.new_fn("serve").vis("pub").set_async(true);
+    scope
+        .new_impl("Bar")
+        .impl_trait("Service")
+        .new_fn("call")
+        .arg_ref_self()
+        .set_async(true);
+
+    let expect = r#"
+async fn fetch() {
+}
+
+pub async fn serve() {
+}
+
+impl Service for Bar {
+    async fn call(&self) {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn rename_items() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo").generic("T").field("one", "T");
+    scope.new_enum("Bar").new_variant("A");
+    scope.new_trait("Baz");
+    scope.new_fn("qux");
+    scope.new_module("quux");
+
+    scope.get_struct_mut("Foo").unwrap().rename("Foo2");
+    scope.get_enum_mut("Bar").unwrap().rename("Bar2");
+    scope.get_trait_mut("Baz").unwrap().rename("Baz2");
+    scope.get_fn_mut("qux").unwrap().rename("qux2");
+    scope.get_module_mut("quux").unwrap().rename("quux2");
+
+    let expect = r#"
+struct Foo2<T> {
+    one: T,
+}
+
+enum Bar2 {
+    A,
+}
+
+trait Baz2 {
+}
+
+fn qux2() {
+}
+
+mod quux2 {
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_with_unsafe() {
+    let mut scope = Scope::new();
+
+    let f = scope.new_fn("read_raw");
+    assert!(!f.is_unsafe());
+    f.set_unsafe(true);
+    assert!(f.is_unsafe());
+
+    scope.new_fn("write_raw").vis("pub(crate)").set_unsafe(true);
+    scope
+        .new_fn("poll_raw")
+        .vis("pub")
+        .set_async(true)
+        .set_unsafe(true);
+    scope
+        .new_fn("ffi_raw")
+        .set_unsafe(true)
+        .extern_abi("C")
+        .line("bar()");
+    scope
+        .new_impl("Foo")
+        .new_fn("get_unchecked")
+        .arg_ref_self()
+        .set_unsafe(true);
+
+    let expect = r#"
+unsafe fn read_raw() {
+}
+
+pub(crate) unsafe fn write_raw() {
+}
+
+pub async unsafe fn poll_raw() {
+}
+
+unsafe extern "C" fn ffi_raw() {
+    bar()
+}
+
+impl Foo {
+    unsafe fn get_unchecked(&self) {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn always_use_where_clause() {
+    let mut scope = Scope::new();
+    scope
+        .new_struct("Foo")
+        .generic("T: Clone")
+        .generic("const N: usize")
+        .bound("T", "Send")
+        .field("items", "[T; N]");
+    scope
+        .new_impl("Foo")
+        .generic("T: std::fmt::Debug")
+        .target_generic("T")
+        .new_fn("show")
+        .generic("W: std::fmt::Write")
+        .arg_ref_self()
+        .arg("w", "&mut W");
+
+    let expect = r#"
+struct Foo<T: Clone, const N: usize>
+where T: Send,
+{
+    items: [T; N],
+}
+
+impl<T: std::fmt::Debug> Foo<T> {
+    fn show<W: std::fmt::Write>(&self, w: &mut W) {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+
+    let config = FormatterConfig {
+        always_use_where_clause: true,
+        ..Default::default()
+    };
+
+    let expect = r#"
+struct Foo<T, const N: usize>
+where T: Clone,
+      T: Send,
+{
+    items: [T; N],
+}
+
+impl<T> Foo<T>
+where T: std::fmt::Debug,
+{
+    fn show<W>(&self, w: &mut W)
+    where W: std::fmt::Write,
+    {
+    }
+}"#;
+
+    assert_eq!(scope.fmt_with_config(&config), &expect[1..]);
+}
+
+#[test]
+fn const_fn() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("zero")
+        .vis("pub")
+        .set_const(true)
+        .ret("u32")
+        .line("0");
+    scope
+        .new_impl("Foo")
+        .new_fn("from_raw")
+        .vis("pub")
+        .set_const(true)
+        .set_unsafe(true)
+        .arg("raw", "u32")
+        .ret("Self")
+        .line("Foo(raw)");
+    scope.new_fn("load").set_const(true).set_async(true);
+
+    assert!(scope.get_fn("zero").unwrap().is_const());
+
+    let expect = r#"
+pub const fn zero() -> u32 {
+    0
+}
+
+impl Foo {
+    pub const unsafe fn from_raw(raw: u32) -> Self {
+        Foo(raw)
+    }
+}
+
+const async fn load() {
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_with_derive_debug_display() {
+    let mut scope = Scope::new();
+
+    let mut foo = Struct::new("Foo");
+    foo.generic("T").field("one", "T");
+    let display = foo.with_derive_debug_display();
+
+    scope.push_struct(foo);
+    scope.push_impl(display);
+
+    let expect = r#"
+#[derive(Debug)]
+struct Foo<T> {
+    one: T,
+}
+
+impl<T> std::fmt::Display for Foo<T>
+where T: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_set_extern() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("callback")
+        .vis("pub")
+        .set_extern("C")
+        .arg("data", "*mut u8")
+        .line("let _ = data;");
+    scope
+        .new_fn("window_proc")
+        .set_extern("system")
+        .ret("isize")
+        .line("0");
+    scope
+        .new_fn("abs")
+        .set_unsafe(true)
+        .set_extern("C")
+        .arg("input", "i32")
+        .ret("i32");
+
+    let expect = r#"
+pub extern "C" fn callback(data: *mut u8) {
+    let _ = data;
+}
+
+extern "system" fn window_proc() -> isize {
+    0
+}
+
+unsafe extern "C" fn abs(input: i32) -> i32;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_lifetimes() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("longest")
+        .generic("T: AsRef<str>")
+        .lifetime("'a")
+        .lifetime("'b")
+        .arg("x", "&'a T")
+        .arg("y", "&'b T")
+        .ret("&'a str")
+        .lifetime_bound("'b", "'a")
+        .line("x.as_ref()");
+
+    let expect = r#"
+fn longest<'a, 'b, T: AsRef<str>>(x: &'a T, y: &'b T) -> &'a str
+where 'b: 'a,
+{
+    x.as_ref()
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn impl_new_qualified_fns() {
+    let mut scope = Scope::new();
+    let imp = scope.new_impl("Foo");
+    imp.new_const_fn("new").vis("pub").ret("Self").line("Foo");
+    imp.new_async_fn("run").arg_ref_self();
+    imp.new_unsafe_fn("get_unchecked")
+        .arg_ref_self()
+        .arg("i", "usize");
+
+    let expect = r#"
+impl Foo {
+    pub const fn new() -> Self {
+        Foo
+    }
+
+    async fn run(&self) {
+    }
+
+    unsafe fn get_unchecked(&self, i: usize) {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_insert_at() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo");
+    scope.new_struct("Bar");
+
+    scope.insert_at(0, Item::Module(Module::new("preamble")));
+    scope.insert_at(2, Item::Raw("// between".to_string()));
+
+    let expect = r#"
+mod preamble {
+}
+
+struct Foo;
+
+// between
+
+struct Bar;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_where_bound() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("process")
+        .generic("T")
+        .generic("U")
+        .arg("t", "T")
+        .arg("u", "U")
+        .where_bound("T", "Clone")
+        .where_bound("U", "std::fmt::Debug")
+        .where_bound("T", "Send");
+
+    let expect = r#"
+fn process<T, U>(t: T, u: U)
+where T: Clone + Send,
+      U: std::fmt::Debug,
+{
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_push_attr() {
+    let mut scope = Scope::new();
+    let f = scope
+        .new_fn("configure")
+        .vis("pub")
+        .push_attr("inline")
+        .push_attr("must_use")
+        .push_attr(format!("allow({})", "clippy::too_many_arguments"))
+        .ret("bool")
+        .line("true");
+
+    assert_eq!(
+        f.attrs(),
+        ["inline", "must_use", "allow(clippy::too_many_arguments)"]
+    );
+
+    let expect = r#"
+#[inline]
+#[must_use]
+#[allow(clippy::too_many_arguments)]
+pub fn configure() -> bool {
+    true
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn module_seal() {
+    let mut scope = Scope::new();
+    let module = scope.new_module("foo");
+    module.new_struct("Bar");
+    module.new_module("inner");
+    module.seal();
+
+    assert!(module.is_sealed());
+
+    module.get_or_new_module("inner").new_fn("still_allowed");
+    module.structs_mut().next().unwrap().field("one", "usize");
+
+    let expect = r#"
+mod foo {
+    struct Bar {
+        one: usize,
+    }
+
+    mod inner {
+        fn still_allowed() {
+        }
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+#[should_panic(expected = "module `foo` is sealed")]
+fn module_seal_push_panics() {
+    let mut module = Module::new("foo");
+    module.seal();
+    module.push_fn(Function::new("bar"));
+}
+
+#[test]
+#[should_panic(expected = "module `foo` is sealed")]
+fn module_seal_scope_panics() {
+    let mut module = Module::new("foo");
+    module.seal();
+    module.scope().new_fn("bar");
+}
+
+#[test]
+#[should_panic(expected = "module `foo` is sealed")]
+fn module_seal_attr_inner_panics() {
+    let mut module = Module::new("foo");
+    module.seal();
+    module.attr_inner("allow(dead_code)");
+}
+
+#[test]
+fn function_body_lines() {
+    let mut scope = Scope::new();
+
+    let mut block = Block::new("if x");
+    block.line("return;");
+
+    let f = scope
+        .new_fn("foo")
+        .arg("x", "bool")
+        .line("let a = 1;")
+        .push_block(block)
+        .line("let b = 2;");
+
+    assert_eq!(
+        f.body_lines().collect::<Vec<_>>(),
+        ["let a = 1;", "let b = 2;"]
+    );
+
+    for line in f.body_lines_mut() {
+        *line = line.replace("let", "let mut");
+    }
+
+    let expect = r#"
+fn foo(x: bool) {
+    let mut a = 1;
+    if x {
+        return;
+    }
+    let mut b = 2;
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+
+    let f = scope.get_fn_mut("foo").unwrap();
+    f.clear_body().line("todo!()");
+    assert_eq!(f.body_lines().count(), 1);
+
+    let expect = r#"
+fn foo(x: bool) {
+    todo!()
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_get_import() {
+    let mut scope = Scope::new();
+    scope.import("std::fmt", "Debug");
+
+    assert!(scope.get_import("std::fmt", "Debug").is_some());
+    assert!(scope.get_import("std::fmt", "Display").is_none());
+    assert!(scope.get_import("std::io", "Debug").is_none());
+
+    scope
+        .get_import_mut("std::fmt", "Debug")
+        .unwrap()
+        .vis("pub");
+
+    let expect = r#"
+pub use std::fmt::Debug;
+"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_tuple_field_with_vis() {
+    let mut scope = Scope::new();
+    scope.new_struct("Meters").vis("pub").tuple_field("f64");
+    scope
+        .new_struct("Pair")
+        .generic("T")
+        .tuple_field_with_vis("pub", "T")
+        .tuple_field_with_vis("pub(crate)", "T")
+        .tuple_field("usize");
+    scope.new_struct("Unit");
+
+    let expect = r#"
+pub struct Meters(f64);
+
+struct Pair<T>(pub T, pub(crate) T, usize);
+
+struct Unit;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_self_type() {
+    let mut pinned = Type::new("Pin");
+    pinned.generic("&mut Self");
+
+    let mut scope = Scope::new();
+    let imp = scope.new_impl("Foo");
+    imp.new_fn("into_inner")
+        .self_type("Box<Self>")
+        .ret("u32")
+        .line("self.0");
+    imp.new_fn("poll")
+        .self_type(pinned)
+        .arg("cx", "&mut Context<'_>");
+
+    let expect = r#"
+impl Foo {
+    fn into_inner(self: Box<Self>) -> u32 {
+        self.0
+    }
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn documentation_getters() {
+    let mut scope = Scope::new();
+    scope.new_struct("Foo").doc("A struct");
+    scope.new_enum("Bar").doc("An enum");
+    scope.new_trait("Baz").doc("A trait");
+    scope.new_fn("qux").doc("A function");
+    scope
+        .new_const("u32")
+        .name("QUUX")
+        .value("1")
+        .doc("A const".into());
+    scope.new_fn("undocumented");
+
+    assert_eq!(
+        scope.get_struct("Foo").unwrap().documentation(),
+        Some("A struct")
+    );
+    assert_eq!(
+        scope.get_enum("Bar").unwrap().documentation(),
+        Some("An enum")
+    );
+    assert_eq!(
+        scope.get_trait("Baz").unwrap().documentation(),
+        Some("A trait")
+    );
+    assert_eq!(
+        scope.get_fn("qux").unwrap().documentation(),
+        Some("A function")
+    );
+    assert_eq!(
+        scope.get_const("QUUX").unwrap().documentation(),
+        Some("A const")
+    );
+    assert_eq!(scope.get_fn("undocumented").unwrap().documentation(), None);
+}
+
+#[test]
+fn struct_set_unit() {
+    let mut scope = Scope::new();
+    scope.new_struct("Marker").derive("Debug");
+    scope.new_struct("Braced").set_unit(false);
+    scope
+        .new_struct("Later")
+        .set_unit(true)
+        .field("one", "usize");
+
+    let expect = r#"
+#[derive(Debug)]
+struct Marker;
+
+struct Braced {
+}
+
+struct Later {
+    one: usize,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn tuple_struct_where_clause() {
+    let mut scope = Scope::new();
+
+    scope
+        .new_struct("Wrapper")
+        .generic("T")
+        .generic("U")
+        .bound("T", "Clone")
+        .bound("U", "std::fmt::Debug")
+        .tuple_field("T")
+        .tuple_field("U");
+
+    let expect = r#"
+struct Wrapper<T, U>(T, U)
+where T: Clone,
+      U: std::fmt::Debug,
+;"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn field_optional() {
+    let mut scope = Scope::new();
+    let foo = scope.new_struct("Foo");
+    foo.field("id", "u64");
+    foo.new_field("name", "String").optional().doc("The name");
+    foo.new_field("tags", "Vec<String>").vis("pub").optional();
+
+    let expect = r#"
+struct Foo {
+    id: u64,
+    /// The name
+    name: Option<String>,
+    pub tags: Option<Vec<String>>,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn struct_with_repr_align() {
+    let mut scope = Scope::new();
+
+    scope
+        .new_struct("Foo")
+        .derive("Clone")
+        .repr("C")
+        .repr_align(8)
+        .field("one", "u8");
+    scope.new_struct("Bar").repr_align(16).tuple_field("u8");
+
+    let expect = r#"
+#[derive(Clone)]
+#[repr(C, align(8))]
+struct Foo {
+    one: u8,
+}
+
+#[repr(align(16))]
+struct Bar(u8);"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn enum_generate_from_str_impl() {
+    let mut scope = Scope::new();
+
+    let mut color = Enum::new("Color");
+    color.new_variant("Red");
+    color.new_variant("DarkBlue");
+
+    scope.push_impl(color.generate_from_str_impl(true));
+    scope.push_impl(color.generate_from_str_impl(false));
+
+    let expect = r#"
+impl std::str::FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Red" => Ok(Self::Red),
+            "DarkBlue" => Ok(Self::DarkBlue),
+            _ => Err(format!("unknown Color variant `{}`", s)),
+        }
+    }
+}
+
+impl std::str::FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "red" => Ok(Self::Red),
+            "darkblue" => Ok(Self::DarkBlue),
+            _ => Err(format!("unknown Color variant `{}`", s)),
+        }
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+#[should_panic(expected = "variants `Foo` and `FOO` both parse from `foo`")]
+fn enum_generate_from_str_impl_case_collision() {
+    let mut e = Enum::new("E");
+    e.new_variant("Foo");
+    e.new_variant("FOO");
+
+    // case sensitive matching is fine
+    e.generate_from_str_impl(true);
+    e.generate_from_str_impl(false);
+}
+
+#[test]
+fn struct_field_visibilities() {
+    let mut scope = Scope::new();
+    let foo = scope.new_struct("Foo");
+    foo.new_field("a", "u8").vis("pub");
+    foo.new_field("b", "u8").vis("pub(crate)");
+    foo.new_field("c", "u8").vis("pub(super)");
+    foo.new_field("d", "u8").vis("pub(in crate::foo)");
+    foo.new_field("e", "u8");
+
+    let expect = r#"
+struct Foo {
+    pub a: u8,
+    pub(crate) b: u8,
+    pub(super) c: u8,
+    pub(in crate::foo) d: u8,
+    e: u8,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_import_group() {
+    let mut std_group = ImportGroup::new("std::collections");
+    std_group
+        .ty("HashMap")
+        .ty("HashSet")
+        .cfg("feature = \"std\"");
+
+    let mut reexports = ImportGroup::new("crate::types");
+    reexports.ty("Foo").ty("Bar").vis("pub");
+
+    let mut scope = Scope::new();
+    scope.import("std::fmt", "Debug");
+    scope.import_group(std_group);
+    scope.import_group(reexports);
+    scope
+        .import("alloc::collections", "BTreeMap")
+        .cfg("not(feature = \"std\")");
+
+    let expect = r#"
+use std::fmt::Debug;
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+pub use crate::types::{Foo, Bar};
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap;
+"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn function_assert_output() {
+    let mut func = Function::new("answer");
+    func.ret("u32").line("42");
+
+    func.assert_output("fn answer() -> u32 {\n    42\n}");
+
+    let result = std::panic::catch_unwind(|| {
+        func.assert_output("fn answer() -> u32 {\n    41\n}");
+    });
+
+    let err = result.unwrap_err();
+    let msg = err.downcast_ref::<String>().unwrap();
+
+    let expect = [
+        "function `answer` output does not match (- expected, + actual):",
+        "  fn answer() -> u32 {",
+        "-     41",
+        "+     42",
+        "  }",
+        "",
+    ];
+
+    assert_eq!(msg, &expect.join("\n"));
+}
+
+#[test]
+fn struct_get_and_remove_field() {
+    let mut scope = Scope::new();
+    let foo = scope.new_struct("Foo");
+    foo.field("a", "u8")
+        .field("b", "u16")
+        .field("c", "u32")
+        .field("d", "u64");
+
+    assert!(foo.get_field("e").is_none());
+    assert!(foo.remove_field("e").is_none());
+    assert_eq!(foo.get_field("b").unwrap().ty.name(), "u16");
+
+    foo.get_field_mut("c").unwrap().vis("pub");
+
+    assert_eq!(foo.remove_field("a").unwrap().name, "a");
+    assert_eq!(foo.remove_field("b").unwrap().name, "b");
+    assert_eq!(foo.remove_field("d").unwrap().name, "d");
+
+    let expect = r#"
+struct Foo {
+    pub c: u32,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+
+    scope.get_struct_mut("Foo").unwrap().remove_field("c");
+
+    assert_eq!(scope.to_string(), "struct Foo;");
+
+    let mut bar = Struct::new("Bar");
+    bar.tuple_field("u8").tuple_field("String");
+
+    assert_eq!(bar.get_field_at(1).unwrap().name(), "String");
+    assert!(bar.get_field_at(2).is_none());
+    assert!(bar.get_field("0").is_none());
+}
+
+#[test]
+fn struct_fields_iter() {
+    let mut scope = Scope::new();
+    let foo = scope.new_struct("Foo");
+
+    assert_eq!(foo.fields().count(), 0);
+
+    foo.field("b", "u8").field("a", "u16");
+
+    let names: Vec<_> = foo.fields().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["b", "a"]);
+    assert_eq!(foo.field_count(), 2);
+
+    for field in foo.fields_mut() {
+        field.annotation("#[serde(default)]");
+    }
+
+    let expect = r#"
+struct Foo {
+    #[serde(default)]
+    b: u8,
+    #[serde(default)]
+    a: u16,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn impl_sort_fns() {
+    let mut scope = Scope::new();
+    let imp = scope.new_impl("Foo");
+    imp.new_fn("charlie").arg_ref_self().arg("x", "u8");
+    imp.new_fn("alpha").arg_ref_self();
+    imp.new_fn("bravo")
+        .arg_ref_self()
+        .arg("x", "u8")
+        .arg("y", "u8");
+
+    imp.sort_fns_alphabetically();
+
+    let expect = r#"
+impl Foo {
+    fn alpha(&self) {
+    }
+
+    fn bravo(&self, x: u8, y: u8) {
+    }
+
+    fn charlie(&self, x: u8) {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+
+    scope
+        .impls_mut()
+        .next()
+        .unwrap()
+        .sort_fns_by(|func| std::cmp::Reverse(func.name().len()));
+
+    let expect = r#"
+impl Foo {
+    fn charlie(&self, x: u8) {
+    }
+
+    fn alpha(&self) {
+    }
+
+    fn bravo(&self, x: u8, y: u8) {
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_render_item() {
+    let mut foo = Struct::new("Foo");
+    foo.field("one", "usize");
+
+    let expect = r#"
+struct Foo {
+    one: usize,
+}"#;
+
+    assert_eq!(Scope::render_item(&Item::Struct(foo)), &expect[1..]);
+
+    let mut bar = Function::new("bar");
+    bar.line("baz()");
+
+    assert_eq!(
+        Scope::render_item(&Item::Function(bar)),
+        "fn bar() {\n    baz()\n}"
+    );
+    assert_eq!(Scope::render_item(&Item::Raw("// done".into())), "// done");
+}
+
+#[test]
+fn struct_reorder_fields() {
+    let mut scope = Scope::new();
+    let foo = scope.new_struct("Foo");
+    foo.repr("C")
+        .field("a", "u8")
+        .field("b", "u16")
+        .field("c", "u32")
+        .field("d", "u64");
+
+    foo.reorder_fields(&["d", "c", "b", "a"]).unwrap();
+
+    let names: Vec<_> = foo.fields().map(|f| f.name.as_str()).collect();
+    assert_eq!(names, ["d", "c", "b", "a"]);
+
+    foo.reorder_fields(&["a", "c"]).unwrap();
+
+    assert_eq!(
+        foo.reorder_fields(&["b", "e"]).unwrap_err(),
+        ReorderError::UnknownField("e".to_string())
+    );
+    assert_eq!(
+        foo.reorder_fields(&["b", "b"]).unwrap_err(),
+        ReorderError::DuplicateField("b".to_string())
+    );
+
+    let expect = r#"
+#[repr(C)]
+struct Foo {
+    a: u8,
+    c: u32,
+    d: u64,
+    b: u16,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn field_attr() {
+    let mut scope = Scope::new();
+    let foo = scope.new_struct("Foo");
+    foo.new_field("id", "u64")
+        .vis("pub")
+        .attr("serde(rename = \"ID\")")
+        .attr("cfg(feature = \"ids\")");
+    foo.new_field("cache", "Vec<u8>").attr("serde(skip)");
+    foo.field("plain", "bool");
+
+    let expect = r#"
+struct Foo {
+    #[serde(rename = "ID")]
+    #[cfg(feature = "ids")]
+    pub id: u64,
+    #[serde(skip)]
+    cache: Vec<u8>,
+    plain: bool,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn function_cfg() {
+    let mut scope = Scope::new();
+    scope
+        .new_fn("load")
+        .vis("pub")
+        .cfg("feature = \"std\"")
+        .attr("inline")
+        .cfg("unix")
+        .cfg("feature = \"std\"");
+
+    let expect = r#"
+#[cfg(feature = "std")]
+#[inline]
+#[cfg(unix)]
+pub fn load() {
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_remove_last_import_of_path() {
+    let mut scope = Scope::new();
+    scope.import("std::fmt", "Debug");
+    scope.new_struct("Foo");
+
+    assert!(scope.remove_import("std::fmt", "Debug"));
+    assert!(!scope.remove_import("std::fmt", "Debug"));
+    assert!(!scope.has_imports());
+
+    assert_eq!(scope.to_string(), "struct Foo;");
+}
+
+#[test]
+fn enum_where_clause() {
+    let mut scope = Scope::new();
+
+    let foo = scope
+        .new_enum("Foo")
+        .generic("T")
+        .generic("U")
+        .bound("T", "std::hash::Hash")
+        .bound("U", "Clone");
+    foo.new_variant("Bar").tuple("T");
+    foo.new_variant("Baz").tuple("U");
+    foo.new_variant("Qux");
+
+    let expect = r#"
+enum Foo<T, U>
+where T: std::hash::Hash,
+      U: Clone,
+{
+    Bar(T),
+    Baz(U),
+    Qux,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn enum_with_repr_and_derive() {
+    let mut scope = Scope::new();
+
+    let status = scope
+        .new_enum("Status")
+        .vis("pub")
+        .derive("Clone")
+        .derive("Copy")
+        .repr("C, i32");
+    status.new_variant("Ok").discriminant("0");
+    status.new_variant("Failed").discriminant("-1");
+
+    scope
+        .new_enum("Unchecked")
+        .repr("not_a_repr")
+        .new_variant("A");
+
+    let expect = r#"
+#[derive(Clone, Copy)]
+#[repr(C, i32)]
+pub enum Status {
+    Ok = 0,
+    Failed = -1,
+}
+
+#[repr(not_a_repr)]
+enum Unchecked {
+    A,
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_feature() {
+    let mut scope = Scope::new();
+    scope
+        .feature("never_type")
+        .recursion_limit(256)
+        .feature("async_fn_in_trait")
+        .feature("never_type");
+    scope.new_fn("diverge").ret_never().line("loop {}");
+
+    let expect = r#"
+#![feature(never_type, async_fn_in_trait)]
+#![recursion_limit = "256"]
+
+fn diverge() -> ! {
+    loop {}
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}
+
+#[test]
+fn scope_feature_in_module() {
+    let mut scope = Scope::new();
+    scope.feature("never_type");
+
+    let module = scope.new_module("foo");
+    module.scope().feature("never_type").feature("let_chains");
+    module.new_module("bar").scope().feature("try_blocks");
+    module.new_fn("diverge").ret_never().line("loop {}");
+
+    let expect = r#"
+#![feature(never_type, let_chains, try_blocks)]
+
+mod foo {
+    mod bar {
+    }
+
+    fn diverge() -> ! {
+        loop {}
+    }
+}"#;
+
+    assert_eq!(scope.to_string(), &expect[1..]);
+}